@@ -1,18 +1,32 @@
-use std::{cmp::Ordering, net::SocketAddr, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    time::Duration,
+};
 
 use anyhow::Context;
+use async_compression::tokio::bufread::{
+    BrotliDecoder, BrotliEncoder, GzipDecoder, GzipEncoder, ZstdDecoder, ZstdEncoder,
+};
 use axum::{
+    body::Body,
     extract::State,
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::PrometheusHandle;
 use redis::AsyncCommands;
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgPoolOptions, PgPool, FromRow};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio_util::io::StreamReader;
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
@@ -43,6 +57,126 @@ struct MemoryDocument {
     tags: Vec<String>,
     created_at: String,
     updated_at: String,
+    // "none" for plaintext `content` (including every document indexed before
+    // this field existed, via serde's default); otherwise the codec `content`
+    // is hex-encoded compressed bytes in, and `content_length` below is the
+    // original decompressed byte length.
+    #[serde(default = "default_content_codec")]
+    content_codec: String,
+    #[serde(default)]
+    content_length: usize,
+}
+
+fn default_content_codec() -> String {
+    "none".to_string()
+}
+
+// Per-deployment tuning for search/ranking, persisted in the `settings`
+// singleton table. Lets relevance be tuned without redeploying: which fields
+// are searched (and with what boost), whether fuzzy matching is on, and how
+// BM25 relevance, importance, and recency are blended into the final ranking
+// score. `displayed_fields` is stored and returned by memory.settings.get, but
+// search and list responses are not filtered by it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+struct MemorySettings {
+    // [[field, boost], ...], e.g. [["content", 2.0], ["summary", 1.0]]
+    searchable_fields: serde_json::Value,
+    fuzziness: bool,
+    displayed_fields: Vec<String>,
+    relevance_weight: f32,
+    importance_weight: f32,
+    recency_weight: f32,
+    // "linear" blends relevance/importance/recency with the weights above;
+    // "rrf" ignores them and fuses the three signals' *rankings* instead (see
+    // `rrf_scores`) so BM25/importance/recency don't need comparable scales.
+    ranking_mode: String,
+    rrf_k: f32,
+    rrf_signals: Vec<String>,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            searchable_fields: serde_json::json!([["content", 2.0], ["summary", 1.0], ["tags", 1.0]]),
+            fuzziness: true,
+            displayed_fields: vec![
+                "content".to_string(),
+                "summary".to_string(),
+                "tags".to_string(),
+                "importance_score".to_string(),
+                "created_at".to_string(),
+            ],
+            relevance_weight: 0.0,
+            importance_weight: 0.6,
+            recency_weight: 0.4,
+            ranking_mode: "linear".to_string(),
+            rrf_k: 60.0,
+            rrf_signals: vec![
+                "relevance".to_string(),
+                "importance".to_string(),
+                "recency".to_string(),
+            ],
+        }
+    }
+}
+
+impl MemorySettings {
+    // OpenSearch `multi_match` field list with `^boost` suffixes, e.g. `content^2`.
+    fn opensearch_fields(&self) -> Vec<String> {
+        self.searchable_fields
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|entry| {
+                        let pair = entry.as_array()?;
+                        let field = pair.first()?.as_str()?;
+                        let boost = pair.get(1).and_then(|b| b.as_f64()).unwrap_or(1.0);
+                        Some(format!("{field}^{boost}"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_else(|| MemorySettings::default().opensearch_fields())
+    }
+}
+
+// Per-user overrides on top of the deployment-wide `MemorySettings`: ranking
+// weighting, recency decay window, default list limit, and stop-tags to
+// exclude from faceting. A row only exists once a user has customized
+// something; `None` fields fall back to `MemorySettings` (or this function's
+// own defaults) via `resolve_user_settings`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+struct UserSettings {
+    user_id: String,
+    importance_weight: Option<f32>,
+    recency_weight: Option<f32>,
+    recency_decay_days: Option<f32>,
+    default_limit: Option<i32>,
+    stop_tags: Vec<String>,
+}
+
+// `UserSettings` (or its absence) merged with the deployment-wide
+// `MemorySettings`, into the concrete values `memory.search` actually ranks
+// with.
+#[derive(Debug, Clone, Serialize)]
+struct ResolvedUserSettings {
+    importance_weight: f32,
+    recency_weight: f32,
+    recency_decay_days: f32,
+    default_limit: usize,
+    stop_tags: Vec<String>,
+}
+
+fn resolve_user_settings(global: &MemorySettings, overrides: Option<&UserSettings>) -> ResolvedUserSettings {
+    ResolvedUserSettings {
+        importance_weight: overrides.and_then(|o| o.importance_weight).unwrap_or(global.importance_weight),
+        recency_weight: overrides.and_then(|o| o.recency_weight).unwrap_or(global.recency_weight),
+        recency_decay_days: overrides.and_then(|o| o.recency_decay_days).unwrap_or(30.0),
+        default_limit: overrides
+            .and_then(|o| o.default_limit)
+            .map(|l| l.max(1) as usize)
+            .unwrap_or(5),
+        stop_tags: overrides.map(|o| o.stop_tags.clone()).unwrap_or_default(),
+    }
 }
 
 #[derive(Clone)]
@@ -50,6 +184,7 @@ struct AppState {
     db: PgPool,
     opensearch: OpenSearchClient,
     redis: Option<redis::aio::ConnectionManager>,
+    metrics: PrometheusHandle,
 }
 
 #[derive(Clone)]
@@ -57,15 +192,63 @@ struct OpenSearchClient {
     client: HttpClient,
     base_url: String,
     index: String,
+    // Codec applied to `content` on write ("none" disables compression
+    // outright); see `compress_for_storage`'s comment for the full tradeoff.
+    content_codec: String,
+    compression_threshold_bytes: usize,
 }
 
 impl OpenSearchClient {
     fn new(base_url: &str) -> Self {
+        let content_codec = std::env::var("OPENMEMORY_CONTENT_CODEC").unwrap_or_else(|_| "zstd".to_string());
+        let compression_threshold_bytes = std::env::var("OPENMEMORY_COMPRESSION_THRESHOLD_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(512);
+
         Self {
             client: HttpClient::new(),
             base_url: base_url.trim_end_matches('/').to_string(),
             index: "memories".to_string(),
+            content_codec,
+            compression_threshold_bytes,
+        }
+    }
+
+    // `content` is also the field OpenSearch's standard analyzer indexes for
+    // full-text search, so compressing it trades away BM25 relevance on that
+    // document (the analyzer would tokenize the hex blob, not real words) in
+    // exchange for a smaller `_source` payload. We only take that trade above
+    // `compression_threshold_bytes`, where the size win is worth it; small
+    // documents are left as plain, searchable text.
+    async fn compress_for_storage(&self, doc: &MemoryDocument) -> anyhow::Result<MemoryDocument> {
+        if self.content_codec == "none" || doc.content.len() < self.compression_threshold_bytes {
+            return Ok(doc.clone());
         }
+
+        let compressed = compress_bytes(&self.content_codec, doc.content.as_bytes().to_vec()).await?;
+        Ok(MemoryDocument {
+            content: hex_encode(&compressed),
+            content_codec: self.content_codec.clone(),
+            content_length: doc.content.len(),
+            ..doc.clone()
+        })
+    }
+
+    // Inverse of `compress_for_storage`; a no-op for documents stored with
+    // `content_codec: "none"`, including every document indexed before this
+    // field existed.
+    async fn decompress_doc(&self, doc: &mut MemoryDocument) -> anyhow::Result<()> {
+        if doc.content_codec == "none" {
+            return Ok(());
+        }
+
+        let compressed = hex_decode(&doc.content)?;
+        let plain = decompress_bytes(&doc.content_codec, compressed).await?;
+        doc.content = String::from_utf8(plain).context("decompressed content was not valid UTF-8")?;
+        doc.content_codec = "none".to_string();
+        doc.content_length = 0;
+        Ok(())
     }
 
     async fn create_index(&self) -> anyhow::Result<()> {
@@ -93,7 +276,9 @@ impl OpenSearchClient {
                     "importance_score": { "type": "float" },
                     "tags": { "type": "keyword" },
                     "created_at": { "type": "date" },
-                    "updated_at": { "type": "date" }
+                    "updated_at": { "type": "date" },
+                    "content_codec": { "type": "keyword" },
+                    "content_length": { "type": "integer" }
                 }
             }
         });
@@ -116,10 +301,11 @@ impl OpenSearchClient {
 
     async fn index_document(&self, doc: &MemoryDocument) -> anyhow::Result<()> {
         let url = format!("{}/{}/_doc/{}", self.base_url, self.index, doc.id);
+        let doc = self.compress_for_storage(doc).await?;
 
         let resp = self.client
             .put(&url)
-            .json(doc)
+            .json(&doc)
             .send()
             .await?;
 
@@ -131,15 +317,36 @@ impl OpenSearchClient {
         Ok(())
     }
 
-    async fn search(&self, query: &str, user_id: Option<&str>, limit: usize) -> anyhow::Result<Vec<MemoryDocument>> {
+    // Returns each hit alongside OpenSearch's raw `_score` so callers can fold
+    // BM25 relevance into `compute_combined_score` next to importance/recency.
+    async fn search(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        settings: &MemorySettings,
+    ) -> anyhow::Result<Vec<(MemoryDocument, f32)>> {
+        let start = std::time::Instant::now();
+        let result = self.search_inner(query, user_id, limit, settings).await;
+        histogram!("opensearch_query_duration_ms").record(start.elapsed().as_secs_f64() * 1000.0);
+        result
+    }
+
+    async fn search_inner(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        settings: &MemorySettings,
+    ) -> anyhow::Result<Vec<(MemoryDocument, f32)>> {
         let url = format!("{}/{}/_search", self.base_url, self.index);
 
         let mut must_clauses = vec![
             serde_json::json!({
                 "multi_match": {
                     "query": query,
-                    "fields": ["content^2", "summary", "tags"],
-                    "fuzziness": "AUTO"
+                    "fields": settings.opensearch_fields(),
+                    "fuzziness": if settings.fuzziness { "AUTO" } else { "0" }
                 }
             })
         ];
@@ -174,15 +381,20 @@ impl OpenSearchClient {
         let result: serde_json::Value = resp.json().await?;
         let hits = result["hits"]["hits"].as_array();
 
-        let docs: Vec<MemoryDocument> = hits
+        let mut docs: Vec<(MemoryDocument, f32)> = hits
             .map(|arr| {
                 arr.iter()
                     .filter_map(|hit| {
-                        serde_json::from_value(hit["_source"].clone()).ok()
+                        let doc: MemoryDocument = serde_json::from_value(hit["_source"].clone()).ok()?;
+                        let score = hit["_score"].as_f64().unwrap_or(0.0) as f32;
+                        Some((doc, score))
                     })
                     .collect()
             })
             .unwrap_or_default();
+        for (doc, _) in docs.iter_mut() {
+            self.decompress_doc(doc).await?;
+        }
 
         Ok(docs)
     }
@@ -211,13 +423,52 @@ impl OpenSearchClient {
         let result: serde_json::Value = resp.json().await?;
         let hits = result["hits"]["hits"].as_array();
 
-        let docs: Vec<MemoryDocument> = hits
+        let mut docs: Vec<MemoryDocument> = hits
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|hit| serde_json::from_value(hit["_source"].clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for doc in docs.iter_mut() {
+            self.decompress_doc(doc).await?;
+        }
+
+        Ok(docs)
+    }
+
+    // Every indexed document, for `memory.reconcile` to diff against Postgres.
+    // Not paginated (a real deployment would scroll/PIT this); fine for the
+    // reconciliation sweep's current scale.
+    async fn all_documents(&self) -> anyhow::Result<Vec<MemoryDocument>> {
+        let url = format!("{}/{}/_search", self.base_url, self.index);
+
+        let search_body = serde_json::json!({
+            "size": 10_000,
+            "query": { "match_all": {} },
+            "_source": true
+        });
+
+        let resp = self.client.post(&url).json(&search_body).send().await?;
+
+        if !resp.status().is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("all_documents search failed: {}", body);
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let hits = result["hits"]["hits"].as_array();
+
+        let mut docs: Vec<MemoryDocument> = hits
             .map(|arr| {
                 arr.iter()
                     .filter_map(|hit| serde_json::from_value(hit["_source"].clone()).ok())
                     .collect()
             })
             .unwrap_or_default();
+        for doc in docs.iter_mut() {
+            self.decompress_doc(doc).await?;
+        }
 
         Ok(docs)
     }
@@ -237,10 +488,46 @@ impl OpenSearchClient {
         }
 
         let result: serde_json::Value = resp.json().await?;
-        let doc: MemoryDocument = serde_json::from_value(result["_source"].clone())?;
+        let mut doc: MemoryDocument = serde_json::from_value(result["_source"].clone())?;
+        self.decompress_doc(&mut doc).await?;
         Ok(Some(doc))
     }
 
+    // One `_mget` request for a batch of ids, instead of one `get_document`
+    // round-trip per id. Missing/not-found ids are simply absent from the map.
+    async fn multi_get(&self, ids: &[String]) -> anyhow::Result<HashMap<String, MemoryDocument>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let url = format!("{}/{}/_mget", self.base_url, self.index);
+        let body = serde_json::json!({ "ids": ids });
+
+        let resp = self.client.post(&url).json(&body).send().await?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("multi_get failed: {}", text);
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let docs = result["docs"].as_array().cloned().unwrap_or_default();
+
+        let mut out = HashMap::with_capacity(docs.len());
+        for doc in docs {
+            if !doc["found"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            let Some(id) = doc["_id"].as_str() else { continue };
+            if let Ok(mut parsed) = serde_json::from_value::<MemoryDocument>(doc["_source"].clone()) {
+                self.decompress_doc(&mut parsed).await?;
+                out.insert(id.to_string(), parsed);
+            }
+        }
+
+        Ok(out)
+    }
+
     async fn delete_document(&self, id: &str) -> anyhow::Result<bool> {
         let url = format!("{}/{}/_doc/{}", self.base_url, self.index, id);
 
@@ -248,6 +535,56 @@ impl OpenSearchClient {
 
         Ok(resp.status().is_success())
     }
+
+    // One `_bulk` request per batch of documents, instead of one `index_document`
+    // round-trip per record. An `Ok(())` at index `i` of the returned vec means
+    // document `i`'s bulk item reported success; `Err` carries OpenSearch's
+    // reported reason.
+    async fn bulk_index(&self, docs: &[MemoryDocument]) -> anyhow::Result<Vec<Result<(), String>>> {
+        if docs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let url = format!("{}/_bulk", self.base_url);
+        let mut body = String::new();
+        for doc in docs {
+            let doc = self.compress_for_storage(doc).await?;
+            body.push_str(&serde_json::to_string(&serde_json::json!({ "index": { "_index": self.index, "_id": doc.id } }))?);
+            body.push('\n');
+            body.push_str(&serde_json::to_string(&doc)?);
+            body.push('\n');
+        }
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("bulk index failed: {text}");
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let items = result["items"].as_array().cloned().unwrap_or_default();
+
+        Ok((0..docs.len())
+            .map(|i| match items.get(i) {
+                Some(item) => {
+                    let status = item["index"]["status"].as_u64().unwrap_or(500);
+                    if (200..300).contains(&status) {
+                        Ok(())
+                    } else {
+                        Err(item["index"]["error"]["reason"].as_str().unwrap_or("unknown bulk error").to_string())
+                    }
+                }
+                None => Err("missing bulk response item".to_string()),
+            })
+            .collect())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -283,6 +620,14 @@ enum McpRequest {
         user_id: Option<String>,
         #[serde(default)]
         source: Option<String>, // "all", "postgres", "opensearch"
+        #[serde(default)]
+        tags: Option<Vec<String>>,
+        // "any" (default): row has at least one of `tags`. "all": row has every tag.
+        #[serde(default)]
+        tags_mode: Option<String>,
+        // Returns a tag -> count distribution over the filtered set in the response.
+        #[serde(default)]
+        facets: bool,
     },
 
     #[serde(rename = "memory.get")]
@@ -307,17 +652,156 @@ enum McpRequest {
     MemoryDelete {
         id: Uuid,
     },
+
+    #[serde(rename = "memory.task.get")]
+    MemoryTaskGet {
+        task_uid: Uuid,
+    },
+
+    #[serde(rename = "memory.task.list")]
+    MemoryTaskList {
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+
+    #[serde(rename = "memory.settings.get")]
+    MemorySettingsGet,
+
+    #[serde(rename = "memory.settings.update")]
+    MemorySettingsUpdate {
+        #[serde(default)]
+        searchable_fields: Option<serde_json::Value>,
+        #[serde(default)]
+        fuzziness: Option<bool>,
+        #[serde(default)]
+        displayed_fields: Option<Vec<String>>,
+        #[serde(default)]
+        relevance_weight: Option<f32>,
+        #[serde(default)]
+        importance_weight: Option<f32>,
+        #[serde(default)]
+        recency_weight: Option<f32>,
+        // "linear" (weighted sum of relevance/importance/recency) or "rrf"
+        // (Reciprocal Rank Fusion over the same three signals).
+        #[serde(default)]
+        ranking_mode: Option<String>,
+        #[serde(default)]
+        rrf_k: Option<f32>,
+        #[serde(default)]
+        rrf_signals: Option<Vec<String>>,
+    },
+
+    #[serde(rename = "memory.batch")]
+    MemoryBatch {
+        ops: Vec<BatchOp>,
+    },
+
+    // Admin operation: diffs `memory_index` against OpenSearch's `_doc`s and
+    // repairs the drift only when `repair` is set and `dry_run` is false
+    // (`dry_run` defaults to true; see `reconcile`).
+    #[serde(rename = "memory.reconcile")]
+    MemoryReconcile {
+        #[serde(default)]
+        repair: bool,
+        #[serde(default = "default_true")]
+        dry_run: bool,
+    },
+
+    // Polls the OpenSearch-sync update log a memory.update/memory.delete
+    // enqueued into (see memory_tasks / run_memory_task_worker).
+    #[serde(rename = "memory.task_status")]
+    TaskStatus {
+        update_id: i64,
+    },
+
+    // Per-user ranking/indexing overrides (see UserSettings), distinct from
+    // the deployment-wide memory.settings.* pair above.
+    #[serde(rename = "user_settings.get")]
+    UserSettingsGet {
+        user_id: String,
+    },
+
+    #[serde(rename = "user_settings.update")]
+    UserSettingsUpdate {
+        user_id: String,
+        #[serde(default)]
+        importance_weight: Option<f32>,
+        #[serde(default)]
+        recency_weight: Option<f32>,
+        #[serde(default)]
+        recency_decay_days: Option<f32>,
+        #[serde(default)]
+        default_limit: Option<i32>,
+        #[serde(default)]
+        stop_tags: Option<Vec<String>>,
+    },
 }
 
-#[derive(Debug, Serialize)]
+fn default_true() -> bool {
+    true
+}
+
+// A narrower mirror of `McpRequest`'s mutation/read variants, scoped to what
+// `memory.batch` supports. Kept separate from `McpRequest` (rather than
+// nesting `McpRequest` itself) so a batch can't contain another batch.
+#[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
-enum McpResponse {
-    #[serde(rename = "memory.save.result")]
-    MemorySaveResult {
+enum BatchOp {
+    #[serde(rename = "memory.save")]
+    MemorySave {
+        content: String,
+        #[serde(default)]
+        summary: Option<String>,
+        #[serde(default)]
+        importance: Option<f32>,
+        #[serde(default)]
+        tags: Option<Vec<String>>,
+        #[serde(default)]
+        user_id: Option<String>,
+    },
+
+    #[serde(rename = "memory.update")]
+    MemoryUpdate {
+        id: Uuid,
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        summary: Option<String>,
+        #[serde(default)]
+        importance: Option<f32>,
+        #[serde(default)]
+        tags: Option<Vec<String>>,
+    },
+
+    #[serde(rename = "memory.delete")]
+    MemoryDelete {
+        id: Uuid,
+    },
+
+    #[serde(rename = "memory.get")]
+    MemoryGet {
         id: Uuid,
-        created_at: DateTime<Utc>,
     },
+}
+
+impl BatchOp {
+    fn into_request(self) -> McpRequest {
+        match self {
+            BatchOp::MemorySave { content, summary, importance, tags, user_id } => {
+                McpRequest::MemorySave { content, summary, importance, tags, user_id }
+            }
+            BatchOp::MemoryUpdate { id, content, summary, importance, tags } => {
+                McpRequest::MemoryUpdate { id, content, summary, importance, tags }
+            }
+            BatchOp::MemoryDelete { id } => McpRequest::MemoryDelete { id },
+            BatchOp::MemoryGet { id } => McpRequest::MemoryGet { id },
+        }
+    }
+}
 
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+enum McpResponse {
     #[serde(rename = "memory.search.result")]
     MemorySearchResult {
         query: String,
@@ -329,6 +813,10 @@ enum McpResponse {
         memories: Vec<ListResult>,
         total: usize,
         source: String,
+        // tag -> count over the filtered set, present only when `facets: true`
+        // was requested.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        facets: Option<HashMap<String, usize>>,
     },
 
     #[serde(rename = "memory.get.result")]
@@ -336,19 +824,101 @@ enum McpResponse {
         memory: Option<FullMemory>,
     },
 
-    #[serde(rename = "memory.update.result")]
-    MemoryUpdateResult {
-        id: Uuid,
-        updated_at: DateTime<Utc>,
+    // Returned immediately by memory.save/update/delete: the dual write to
+    // PostgreSQL + OpenSearch happens on the background task worker instead
+    // of inline, so callers poll memory.task.get with this uid for the outcome.
+    #[serde(rename = "memory.task.enqueued")]
+    MemoryTaskEnqueued {
+        task_uid: Uuid,
+        status: String,
     },
 
-    #[serde(rename = "memory.delete.result")]
-    MemoryDeleteResult {
-        id: Uuid,
-        deleted: bool,
+    #[serde(rename = "memory.task.result")]
+    MemoryTaskResult {
+        task: Option<TaskRecord>,
+    },
+
+    #[serde(rename = "memory.task.list.result")]
+    MemoryTaskListResult {
+        tasks: Vec<TaskRecord>,
+    },
+
+    #[serde(rename = "memory.settings.result")]
+    MemorySettingsResult {
+        settings: MemorySettings,
+    },
+
+    #[serde(rename = "memory.batch.result")]
+    MemoryBatchResult {
+        results: Vec<BatchOpOutcome>,
+    },
+
+    #[serde(rename = "memory.reconcile.result")]
+    MemoryReconcileResult {
+        dry_run: bool,
+        report: ReconcileReport,
+    },
+
+    #[serde(rename = "memory.task_status.result")]
+    TaskStatusResult {
+        status: MemoryTaskStatus,
+    },
+
+    #[serde(rename = "user_settings.result")]
+    UserSettingsResult {
+        user_id: String,
+        settings: ResolvedUserSettings,
+    },
+}
+
+// State of a pending OpenSearch-sync row in `memory_tasks`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum MemoryTaskStatus {
+    Enqueued,
+    Processing,
+    Processed { ok: bool, err: Option<String> },
+    Unknown,
+}
+
+// Drift between `memory_index` and OpenSearch, plus what repair (if any) did.
+#[derive(Debug, Serialize)]
+struct ReconcileReport {
+    missing_in_opensearch: Vec<Uuid>,
+    missing_in_postgres: Vec<Uuid>,
+    reindexed_in_postgres: usize,
+    deleted_orphans: usize,
+}
+
+// Per-operation outcome in a `memory.batch` response, in request order. One
+// failing op is reported here rather than failing the whole batch.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum BatchOpOutcome {
+    Ok(McpResponse),
+    Err {
+        message: String,
+        code: String,
     },
 }
 
+// A row of the `tasks` table: the record of one queued background write.
+#[derive(Debug, Clone, Serialize, FromRow)]
+struct TaskRecord {
+    uid: Uuid,
+    kind: String,
+    status: String,
+    result_id: Option<Uuid>,
+    // Set for memory_update/memory_delete tasks once the OpenSearch sync half
+    // has been enqueued; poll it via memory.task_status to see that half's
+    // own retry progress independently of this outer task's status.
+    memory_task_update_id: Option<i64>,
+    error: Option<String>,
+    enqueued_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
 // List result - combined from both stores
 #[derive(Debug, Serialize)]
 struct ListResult {
@@ -375,8 +945,22 @@ struct SearchResult {
     score: f32,
 }
 
+// One `memory.search` hit with its per-signal scores resolved, before those
+// signals are combined into a final ranking score (see `compute_combined_score`
+// and `rrf_scores`).
+struct RankedCandidate {
+    id: Uuid,
+    content: String,
+    summary: Option<String>,
+    tags: Vec<String>,
+    importance: f32,
+    created_at: DateTime<Utc>,
+    relevance: f32, // normalized OpenSearch BM25 `_score`, 0..1
+    recency: f32,
+}
+
 // Full memory - includes content from OpenSearch
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct FullMemory {
     id: Uuid,
     user_id: Option<String>,
@@ -388,6 +972,100 @@ struct FullMemory {
     updated_at: DateTime<Utc>,
 }
 
+// Structured error taxonomy for the `mcp` handler, modeled on MeiliSearch's
+// `Code`/`ErrCode` split: every failure carries a stable `error_code` clients
+// can match on, a coarse `error_type` category, and the HTTP status to use.
+#[derive(Debug)]
+enum MemoryError {
+    MemoryNotFound,
+    InvalidImportance,
+    InvalidRankingMode,
+    StoreUnavailable { store: &'static str, detail: String },
+    IndexSyncFailed { detail: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorType {
+    InvalidRequest,
+    Internal,
+}
+
+impl ErrorType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorType::InvalidRequest => "invalid_request",
+            ErrorType::Internal => "internal",
+        }
+    }
+}
+
+impl MemoryError {
+    fn error_code(&self) -> &'static str {
+        match self {
+            MemoryError::MemoryNotFound => "memory_not_found",
+            MemoryError::InvalidImportance => "invalid_importance",
+            MemoryError::InvalidRankingMode => "invalid_ranking_mode",
+            MemoryError::StoreUnavailable { .. } => "store_unavailable",
+            MemoryError::IndexSyncFailed { .. } => "index_sync_failed",
+        }
+    }
+
+    fn error_type(&self) -> ErrorType {
+        match self {
+            MemoryError::MemoryNotFound | MemoryError::InvalidImportance | MemoryError::InvalidRankingMode => {
+                ErrorType::InvalidRequest
+            }
+            MemoryError::StoreUnavailable { .. } | MemoryError::IndexSyncFailed { .. } => ErrorType::Internal,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            MemoryError::MemoryNotFound => StatusCode::NOT_FOUND,
+            MemoryError::InvalidImportance => StatusCode::BAD_REQUEST,
+            MemoryError::InvalidRankingMode => StatusCode::BAD_REQUEST,
+            MemoryError::StoreUnavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            MemoryError::IndexSyncFailed { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            MemoryError::MemoryNotFound => "Memory not found".to_string(),
+            MemoryError::InvalidImportance => "importance must be a number between 0.0 and 1.0".to_string(),
+            MemoryError::InvalidRankingMode => "ranking_mode must be \"linear\" or \"rrf\"".to_string(),
+            MemoryError::StoreUnavailable { store, detail } => format!("{store} is unavailable: {detail}"),
+            MemoryError::IndexSyncFailed { detail } => format!("failed to sync memory to the search index: {detail}"),
+        }
+    }
+
+    fn link(&self) -> String {
+        format!("https://docs.openmemory.dev/errors#{}", self.error_code())
+    }
+}
+
+impl std::fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl IntoResponse for MemoryError {
+    fn into_response(self) -> axum::response::Response {
+        counter!("store_errors_total", "code" => self.error_code()).increment(1);
+        let status = self.status();
+        let body = Json(serde_json::json!({
+            "message": self.message(),
+            "code": self.error_code(),
+            "type": self.error_type().as_str(),
+            "link": self.link(),
+        }));
+        (status, body).into_response()
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
@@ -451,13 +1129,41 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    let state = AppState { db, opensearch, redis };
+    let metrics = metrics_exporter_prometheus::PrometheusBuilder::new()
+        .install_recorder()
+        .context("failed to install Prometheus recorder")?;
 
-    let app = Router::new()
-        .route("/health", get(health))
-        .route("/mcp", post(mcp))
-        .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::new().allow_origin(Any).allow_headers(Any).allow_methods(Any))
+    let state = AppState { db, opensearch, redis, metrics };
+
+    // A pool rather than a single worker: claim_next_task's `FOR UPDATE SKIP
+    // LOCKED` already makes concurrent claimers safe, so this is just sizing.
+    let task_worker_pool_size = std::env::var("OPENMEMORY_TASK_WORKER_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(4);
+    for _ in 0..task_worker_pool_size {
+        tokio::spawn(run_task_worker(state.clone()));
+    }
+    tokio::spawn(run_memory_task_worker(state.clone()));
+
+    if let Ok(secs) = std::env::var("OPENMEMORY_RECONCILE_INTERVAL_SECS").map(|v| v.parse::<u64>()) {
+        match secs {
+            Ok(secs) if secs > 0 => {
+                info!("starting periodic reconcile sweep every {secs}s");
+                tokio::spawn(run_reconcile_sweep(state.clone(), Duration::from_secs(secs)));
+            }
+            _ => warn!("OPENMEMORY_RECONCILE_INTERVAL_SECS set but not a positive integer, skipping sweep"),
+        }
+    }
+
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(metrics_handler))
+        .route("/mcp", post(mcp))
+        .route("/import", post(import_memories))
+        .layer(TraceLayer::new_for_http())
+        .layer(CorsLayer::new().allow_origin(Any).allow_headers(Any).allow_methods(Any))
         .with_state(state);
 
     info!(%addr, "starting openmemory server");
@@ -506,10 +1212,196 @@ async fn run_migrations(db: &PgPool) -> anyhow::Result<()> {
         .await
         .ok();
 
+    // Background write queue for memory.save/update/delete (see run_task_worker).
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS tasks (
+            uid UUID PRIMARY KEY,
+            kind TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'enqueued',
+            payload JSONB NOT NULL,
+            result_id UUID,
+            memory_task_update_id BIGINT,
+            error TEXT,
+            enqueued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            started_at TIMESTAMPTZ,
+            finished_at TIMESTAMPTZ
+        )
+        "#,
+    )
+    .execute(db)
+    .await
+    .context("failed to create tasks table")?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status, enqueued_at)")
+        .execute(db)
+        .await
+        .ok();
+
+    // Update log for the OpenSearch half of memory.update/memory.delete (see
+    // run_memory_task_worker): `perform_update`/`perform_delete` enqueue a row
+    // here instead of syncing OpenSearch inline, so a transient OpenSearch
+    // failure gets retried with backoff instead of being silently dropped.
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS memory_tasks (
+            update_id BIGSERIAL PRIMARY KEY,
+            memory_id UUID NOT NULL,
+            kind TEXT NOT NULL,
+            payload JSONB NOT NULL,
+            status TEXT NOT NULL DEFAULT 'enqueued',
+            ok BOOLEAN,
+            error TEXT,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            enqueued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(db)
+    .await
+    .context("failed to create memory_tasks table")?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_memory_tasks_status ON memory_tasks(status, update_id)")
+        .execute(db)
+        .await
+        .ok();
+
+    // Singleton row of search/ranking settings (see MemorySettings).
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS settings (
+            id INTEGER PRIMARY KEY DEFAULT 1,
+            searchable_fields JSONB NOT NULL,
+            fuzziness BOOLEAN NOT NULL,
+            displayed_fields TEXT[] NOT NULL,
+            relevance_weight REAL NOT NULL,
+            importance_weight REAL NOT NULL,
+            recency_weight REAL NOT NULL,
+            ranking_mode TEXT NOT NULL DEFAULT 'linear',
+            rrf_k REAL NOT NULL DEFAULT 60.0,
+            rrf_signals TEXT[] NOT NULL DEFAULT ARRAY['relevance', 'importance', 'recency'],
+            CONSTRAINT settings_singleton CHECK (id = 1)
+        )
+        "#,
+    )
+    .execute(db)
+    .await
+    .context("failed to create settings table")?;
+
+    // Per-user overrides on top of the `settings` singleton above (see
+    // UserSettings). One row per user that has customized anything; users
+    // with no row fall back entirely to the deployment-wide settings.
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_settings (
+            user_id TEXT PRIMARY KEY,
+            importance_weight REAL,
+            recency_weight REAL,
+            recency_decay_days REAL,
+            default_limit INTEGER,
+            stop_tags TEXT[] NOT NULL DEFAULT '{}'
+        )
+        "#,
+    )
+    .execute(db)
+    .await
+    .context("failed to create user_settings table")?;
+
     info!("PostgreSQL migrations complete");
     Ok(())
 }
 
+// Reads the singleton settings row, seeding it with defaults on first use.
+async fn load_settings(db: &PgPool) -> Result<MemorySettings, MemoryError> {
+    let row: Option<MemorySettings> = sqlx::query_as(
+        "SELECT searchable_fields, fuzziness, displayed_fields, relevance_weight, importance_weight, recency_weight, ranking_mode, rrf_k, rrf_signals FROM settings WHERE id = 1",
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+
+    match row {
+        Some(settings) => Ok(settings),
+        None => {
+            let defaults = MemorySettings::default();
+            save_settings(db, &defaults).await?;
+            Ok(defaults)
+        }
+    }
+}
+
+async fn save_settings(db: &PgPool, settings: &MemorySettings) -> Result<(), MemoryError> {
+    sqlx::query(
+        r#"
+        INSERT INTO settings (id, searchable_fields, fuzziness, displayed_fields, relevance_weight, importance_weight, recency_weight, ranking_mode, rrf_k, rrf_signals)
+        VALUES (1, $1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (id) DO UPDATE SET
+            searchable_fields = EXCLUDED.searchable_fields,
+            fuzziness = EXCLUDED.fuzziness,
+            displayed_fields = EXCLUDED.displayed_fields,
+            relevance_weight = EXCLUDED.relevance_weight,
+            importance_weight = EXCLUDED.importance_weight,
+            recency_weight = EXCLUDED.recency_weight,
+            ranking_mode = EXCLUDED.ranking_mode,
+            rrf_k = EXCLUDED.rrf_k,
+            rrf_signals = EXCLUDED.rrf_signals
+        "#,
+    )
+    .bind(&settings.searchable_fields)
+    .bind(settings.fuzziness)
+    .bind(&settings.displayed_fields)
+    .bind(settings.relevance_weight)
+    .bind(settings.importance_weight)
+    .bind(settings.recency_weight)
+    .bind(&settings.ranking_mode)
+    .bind(settings.rrf_k)
+    .bind(&settings.rrf_signals)
+    .execute(db)
+    .await
+    .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+
+    Ok(())
+}
+
+// `None` means this user has never customized anything - callers resolve
+// that with `resolve_user_settings` rather than treating it as an error.
+async fn load_user_settings(db: &PgPool, user_id: &str) -> Result<Option<UserSettings>, MemoryError> {
+    sqlx::query_as(
+        "SELECT user_id, importance_weight, recency_weight, recency_decay_days, default_limit, stop_tags FROM user_settings WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })
+}
+
+async fn save_user_settings(db: &PgPool, settings: &UserSettings) -> Result<(), MemoryError> {
+    sqlx::query(
+        r#"
+        INSERT INTO user_settings (user_id, importance_weight, recency_weight, recency_decay_days, default_limit, stop_tags)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id) DO UPDATE SET
+            importance_weight = EXCLUDED.importance_weight,
+            recency_weight = EXCLUDED.recency_weight,
+            recency_decay_days = EXCLUDED.recency_decay_days,
+            default_limit = EXCLUDED.default_limit,
+            stop_tags = EXCLUDED.stop_tags
+        "#,
+    )
+    .bind(&settings.user_id)
+    .bind(settings.importance_weight)
+    .bind(settings.recency_weight)
+    .bind(settings.recency_decay_days)
+    .bind(settings.default_limit)
+    .bind(&settings.stop_tags)
+    .execute(db)
+    .await
+    .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let _ = tokio::signal::ctrl_c().await;
     warn!("shutdown signal received");
@@ -519,10 +1411,51 @@ async fn health() -> impl IntoResponse {
     (StatusCode::OK, Json(serde_json::json!({ "status": "ok" })))
 }
 
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, state.metrics.render())
+}
+
+// Stable label for per-MCP-type request metrics; mirrors each variant's
+// `#[serde(rename = ...)]` tag without needing the request already consumed.
+fn mcp_op_name(req: &McpRequest) -> &'static str {
+    match req {
+        McpRequest::MemorySave { .. } => "memory.save",
+        McpRequest::MemorySearch { .. } => "memory.search",
+        McpRequest::MemoryList { .. } => "memory.list",
+        McpRequest::MemoryGet { .. } => "memory.get",
+        McpRequest::MemoryUpdate { .. } => "memory.update",
+        McpRequest::MemoryDelete { .. } => "memory.delete",
+        McpRequest::MemoryTaskGet { .. } => "memory.task.get",
+        McpRequest::MemoryTaskList { .. } => "memory.task.list",
+        McpRequest::MemorySettingsGet => "memory.settings.get",
+        McpRequest::MemorySettingsUpdate { .. } => "memory.settings.update",
+        McpRequest::MemoryBatch { .. } => "memory.batch",
+        McpRequest::MemoryReconcile { .. } => "memory.reconcile",
+        McpRequest::TaskStatus { .. } => "memory.task_status",
+        McpRequest::UserSettingsGet { .. } => "user_settings.get",
+        McpRequest::UserSettingsUpdate { .. } => "user_settings.update",
+    }
+}
+
 async fn mcp(
     State(state): State<AppState>,
     Json(req): Json<McpRequest>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<impl IntoResponse, MemoryError> {
+    let op = mcp_op_name(&req);
+    let start = std::time::Instant::now();
+
+    let result = dispatch(&state, req).await;
+
+    histogram!("mcp_request_duration_ms", "op" => op).record(start.elapsed().as_secs_f64() * 1000.0);
+    counter!("mcp_requests_total", "op" => op, "outcome" => if result.is_ok() { "ok" } else { "error" }).increment(1);
+
+    result.map(|(status, resp)| (status, Json(resp)))
+}
+
+// Executes a single MCP operation and returns the status/body to send, without
+// committing to how the caller packages the response — `mcp` wraps it in JSON
+// for the `/mcp` route, `memory.batch` collects it per sub-operation instead.
+async fn dispatch(state: &AppState, req: McpRequest) -> Result<(StatusCode, McpResponse), MemoryError> {
     match req {
         McpRequest::MemorySave {
             content,
@@ -531,63 +1464,27 @@ async fn mcp(
             tags,
             user_id,
         } => {
-            let id = Uuid::new_v4();
-            let importance_score = clamp01(importance.unwrap_or(0.5));
-            let tags = tags.unwrap_or_default();
-            let now = Utc::now();
-
-            // 1. Save index to PostgreSQL
-            let pg_result = sqlx::query(
-                r#"
-                INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $6)
-                "#,
-            )
-            .bind(id)
-            .bind(&user_id)
-            .bind(&summary)
-            .bind(importance_score)
-            .bind(&tags)
-            .bind(now)
-            .execute(&state.db)
-            .await;
-
-            if let Err(e) = pg_result {
-                error!("Failed to save to PostgreSQL: {e}");
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({ "error": "Failed to save memory index" })),
-                ));
+            if let Some(i) = importance {
+                if !(0.0..=1.0).contains(&i) {
+                    return Err(MemoryError::InvalidImportance);
+                }
             }
 
-            // 2. Save full document to OpenSearch
-            let doc = MemoryDocument {
-                id: id.to_string(),
-                user_id: user_id.clone(),
-                content: content.clone(),
-                summary: summary.clone(),
-                importance_score,
-                tags: tags.clone(),
-                created_at: now.to_rfc3339(),
-                updated_at: now.to_rfc3339(),
-            };
-
-            if let Err(e) = state.opensearch.index_document(&doc).await {
-                error!("Failed to save to OpenSearch: {e}");
-                // Rollback PostgreSQL
-                let _ = sqlx::query("DELETE FROM memory_index WHERE id = $1")
-                    .bind(id)
-                    .execute(&state.db)
-                    .await;
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({ "error": "Failed to save memory content" })),
-                ));
-            }
+            // The PostgreSQL + OpenSearch dual write happens on the background
+            // task worker (see run_task_worker); this just queues it.
+            let task_uid = Uuid::new_v4();
+            let payload = serde_json::json!({
+                "content": content,
+                "summary": summary,
+                "importance": importance,
+                "tags": tags,
+                "user_id": user_id,
+            });
+            enqueue_task(state, task_uid, "memory_save", payload).await?;
 
             Ok((
-                StatusCode::OK,
-                Json(McpResponse::MemorySaveResult { id, created_at: now }),
+                StatusCode::ACCEPTED,
+                McpResponse::MemoryTaskEnqueued { task_uid, status: "enqueued".to_string() },
             ))
         }
 
@@ -596,7 +1493,18 @@ async fn mcp(
             limit,
             user_id,
         } => {
-            let limit = limit.unwrap_or(5).clamp(1, 50);
+            let settings = load_settings(&state.db).await?;
+
+            // Per-user overrides (weighting, recency decay, default limit,
+            // stop-tags) on top of the deployment-wide settings above, falling
+            // back to them - and to this function's own defaults - when unset.
+            let user_overrides = match &user_id {
+                Some(uid) => load_user_settings(&state.db, uid).await?,
+                None => None,
+            };
+            let resolved = resolve_user_settings(&settings, user_overrides.as_ref());
+
+            let limit = limit.unwrap_or(resolved.default_limit).clamp(1, 50);
 
             // Try cache first
             let cache_key = format!(
@@ -610,26 +1518,33 @@ async fn mcp(
                 if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
                     if let Ok(cached_results) = serde_json::from_str::<Vec<SearchResult>>(&cached) {
                         info!("cache hit for query: {}", query);
+                        counter!("cache_hits_total", "cache" => "search").increment(1);
                         return Ok((
                             StatusCode::OK,
-                            Json(McpResponse::MemorySearchResult {
+                            McpResponse::MemorySearchResult {
                                 query,
                                 results: cached_results,
-                            }),
+                            },
                         ));
                     }
                 }
+                counter!("cache_misses_total", "cache" => "search").increment(1);
             }
 
             // Search in OpenSearch
             let docs = state.opensearch
-                .search(&query, user_id.as_deref(), limit * 2)
+                .search(&query, user_id.as_deref(), limit * 2, &settings)
                 .await
                 .unwrap_or_default();
 
+            // Normalize OpenSearch's unbounded BM25 `_score` into 0..1 against
+            // the best score in this result set, so it blends with the 0..1
+            // importance/recency scores below.
+            let max_relevance = docs.iter().map(|(_, s)| *s).fold(0.0f32, f32::max);
+
             // Get importance scores from PostgreSQL for ranking
             let ids: Vec<Uuid> = docs.iter()
-                .filter_map(|d| Uuid::parse_str(&d.id).ok())
+                .filter_map(|(d, _)| Uuid::parse_str(&d.id).ok())
                 .collect();
 
             let index_data: Vec<MemoryIndex> = if !ids.is_empty() {
@@ -644,28 +1559,63 @@ async fn mcp(
                 vec![]
             };
 
-            // Combine and score
-            let mut results: Vec<SearchResult> = docs.iter()
-                .filter_map(|doc| {
+            // Combine per-signal scores into the candidate's final ranking score.
+            let candidates: Vec<RankedCandidate> = docs.iter()
+                .filter_map(|(doc, relevance)| {
                     let id = Uuid::parse_str(&doc.id).ok()?;
                     let index = index_data.iter().find(|i| i.id == id);
                     let importance = index.map(|i| i.importance_score).unwrap_or(0.5);
                     let created_at = index.map(|i| i.created_at).unwrap_or_else(Utc::now);
+                    let relevance = if max_relevance > 0.0 { relevance / max_relevance } else { 0.0 };
+                    let recency = recency_score(created_at, resolved.recency_decay_days);
 
-                    let score = compute_combined_score(importance, created_at);
-
-                    Some(SearchResult {
+                    Some(RankedCandidate {
                         id,
                         content: doc.content.clone(),
                         summary: doc.summary.clone(),
                         tags: doc.tags.clone(),
-                        importance_score: importance,
+                        importance,
                         created_at,
-                        score,
+                        relevance,
+                        recency,
                     })
                 })
                 .collect();
 
+            let scores: HashMap<Uuid, f32> = if settings.ranking_mode == "rrf" {
+                rrf_scores(&candidates, &settings)
+            } else {
+                candidates.iter()
+                    .map(|c| {
+                        let score = compute_combined_score(
+                            c.relevance,
+                            c.importance,
+                            c.created_at,
+                            settings.relevance_weight,
+                            resolved.importance_weight,
+                            resolved.recency_weight,
+                            resolved.recency_decay_days,
+                        );
+                        (c.id, score)
+                    })
+                    .collect()
+            };
+
+            let mut results: Vec<SearchResult> = candidates.into_iter()
+                .map(|c| {
+                    let score = scores.get(&c.id).copied().unwrap_or(0.0);
+                    SearchResult {
+                        id: c.id,
+                        content: c.content,
+                        summary: c.summary,
+                        tags: c.tags,
+                        importance_score: c.importance,
+                        created_at: c.created_at,
+                        score,
+                    }
+                })
+                .collect();
+
             results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
             results.truncate(limit);
 
@@ -678,37 +1628,36 @@ async fn mcp(
 
             Ok((
                 StatusCode::OK,
-                Json(McpResponse::MemorySearchResult { query, results }),
+                McpResponse::MemorySearchResult { query, results },
             ))
         }
 
-        McpRequest::MemoryList { limit, user_id, source } => {
+        McpRequest::MemoryList { limit, user_id, source, tags, tags_mode, facets } => {
             let limit = limit.unwrap_or(100).clamp(1, 500);
             let source = source.as_deref().unwrap_or("all");
+            let tags_mode = tags_mode.as_deref().unwrap_or("any");
+
+            // Per-user stop-tags are excluded from facet counts (but not from
+            // the results themselves) - see `resolve_user_settings`.
+            let stop_tags: Vec<String> = match user_id.as_deref() {
+                Some(uid) => {
+                    let global = load_settings(&state.db).await?;
+                    let overrides = load_user_settings(&state.db, uid).await?;
+                    resolve_user_settings(&global, overrides.as_ref()).stop_tags
+                }
+                None => Vec::new(),
+            };
 
             match source {
                 "postgres" => {
                     // List from PostgreSQL only (index data)
-                    let indexes: Vec<MemoryIndex> = match &user_id {
-                        Some(uid) => {
-                            sqlx::query_as(
-                                "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
-                            )
-                            .bind(uid)
-                            .bind(limit as i64)
-                            .fetch_all(&state.db)
-                            .await
-                        }
-                        None => {
-                            sqlx::query_as(
-                                "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index ORDER BY created_at DESC LIMIT $1",
-                            )
-                            .bind(limit as i64)
-                            .fetch_all(&state.db)
-                            .await
-                        }
-                    }
-                    .unwrap_or_default();
+                    let indexes = list_memory_index(&state.db, user_id.as_deref(), tags.as_deref(), tags_mode, limit as i64).await;
+
+                    let facet_counts = if facets {
+                        Some(tag_facets(&state.db, user_id.as_deref(), tags.as_deref(), tags_mode, &stop_tags).await)
+                    } else {
+                        None
+                    };
 
                     let total = indexes.len();
                     let results: Vec<ListResult> = indexes
@@ -726,15 +1675,16 @@ async fn mcp(
 
                     Ok((
                         StatusCode::OK,
-                        Json(McpResponse::MemoryListResult { memories: results, total, source: "postgres".to_string() }),
+                        McpResponse::MemoryListResult { memories: results, total, source: "postgres".to_string(), facets: facet_counts },
                     ))
                 }
 
                 "opensearch" => {
-                    // List from OpenSearch only (full documents)
+                    // List from OpenSearch only (full documents). There's no
+                    // index-backed query to push the tags filter/facets into
+                    // here, so both are computed over the fetched page instead.
                     let docs = state.opensearch.list_all(limit).await.unwrap_or_default();
 
-                    let total = docs.len();
                     let results: Vec<ListResult> = docs
                         .into_iter()
                         .filter_map(|d| {
@@ -756,62 +1706,51 @@ async fn mcp(
                                 updated_at,
                             })
                         })
+                        .filter(|r| tags_match(&r.tags, tags.as_deref(), tags_mode))
                         .collect();
 
+                    let facet_counts = if facets { Some(count_tag_facets(&results, &stop_tags)) } else { None };
+                    let total = results.len();
+
                     Ok((
                         StatusCode::OK,
-                        Json(McpResponse::MemoryListResult { memories: results, total, source: "opensearch".to_string() }),
+                        McpResponse::MemoryListResult { memories: results, total, source: "opensearch".to_string(), facets: facet_counts },
                     ))
                 }
 
                 _ => {
                     // "all" - Combined: Get index from PostgreSQL, content from OpenSearch
-                    let indexes: Vec<MemoryIndex> = match &user_id {
-                        Some(uid) => {
-                            sqlx::query_as(
-                                "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
-                            )
-                            .bind(uid)
-                            .bind(limit as i64)
-                            .fetch_all(&state.db)
-                            .await
-                        }
-                        None => {
-                            sqlx::query_as(
-                                "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index ORDER BY created_at DESC LIMIT $1",
-                            )
-                            .bind(limit as i64)
-                            .fetch_all(&state.db)
-                            .await
-                        }
-                    }
-                    .unwrap_or_default();
-
-                    // Fetch content from OpenSearch for each
-                    let mut results: Vec<ListResult> = Vec::with_capacity(indexes.len());
-                    for idx in &indexes {
-                        let content = state.opensearch
-                            .get_document(&idx.id.to_string())
-                            .await
-                            .ok()
-                            .flatten()
-                            .map(|d| d.content);
-
-                        results.push(ListResult {
+                    let indexes = list_memory_index(&state.db, user_id.as_deref(), tags.as_deref(), tags_mode, limit as i64).await;
+
+                    let facet_counts = if facets {
+                        Some(tag_facets(&state.db, user_id.as_deref(), tags.as_deref(), tags_mode, &stop_tags).await)
+                    } else {
+                        None
+                    };
+
+                    // Fetch content for every row in one OpenSearch multi-get
+                    // instead of one `get_document` round-trip per row.
+                    let ids: Vec<Uuid> = indexes.iter().map(|idx| idx.id).collect();
+                    let id_strings: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                    let docs = state.opensearch.multi_get(&id_strings).await.unwrap_or_default();
+
+                    let results: Vec<ListResult> = indexes
+                        .iter()
+                        .map(|idx| ListResult {
                             id: idx.id,
-                            content,
+                            content: docs.get(&idx.id.to_string()).map(|d| d.content.clone()),
                             summary: idx.summary.clone(),
                             tags: idx.tags.clone(),
                             importance_score: idx.importance_score,
                             created_at: idx.created_at,
                             updated_at: Some(idx.updated_at),
-                        });
-                    }
+                        })
+                        .collect();
 
                     let total = results.len();
                     Ok((
                         StatusCode::OK,
-                        Json(McpResponse::MemoryListResult { memories: results, total, source: "all".to_string() }),
+                        McpResponse::MemoryListResult { memories: results, total, source: "all".to_string(), facets: facet_counts },
                     ))
                 }
             }
@@ -846,7 +1785,7 @@ async fn mcp(
 
             Ok((
                 StatusCode::OK,
-                Json(McpResponse::MemoryGetResult { memory }),
+                McpResponse::MemoryGetResult { memory },
             ))
         }
 
@@ -857,117 +1796,1357 @@ async fn mcp(
             importance,
             tags,
         } => {
-            let now = Utc::now();
+            if let Some(i) = importance {
+                if !(0.0..=1.0).contains(&i) {
+                    return Err(MemoryError::InvalidImportance);
+                }
+            }
 
-            // 1. Update PostgreSQL index
-            let pg_result = sqlx::query(
-                "UPDATE memory_index SET updated_at = $1, summary = COALESCE($2, summary), importance_score = COALESCE($3, importance_score), tags = COALESCE($4, tags) WHERE id = $5",
+            let task_uid = Uuid::new_v4();
+            let payload = serde_json::json!({
+                "id": id,
+                "content": content,
+                "summary": summary,
+                "importance": importance,
+                "tags": tags,
+            });
+            enqueue_task(state, task_uid, "memory_update", payload).await?;
+
+            Ok((
+                StatusCode::ACCEPTED,
+                McpResponse::MemoryTaskEnqueued { task_uid, status: "enqueued".to_string() },
+            ))
+        }
+
+        McpRequest::MemoryDelete { id } => {
+            let task_uid = Uuid::new_v4();
+            let payload = serde_json::json!({ "id": id });
+            enqueue_task(state, task_uid, "memory_delete", payload).await?;
+
+            Ok((
+                StatusCode::ACCEPTED,
+                McpResponse::MemoryTaskEnqueued { task_uid, status: "enqueued".to_string() },
+            ))
+        }
+
+        McpRequest::MemoryTaskGet { task_uid } => {
+            let task: Option<TaskRecord> = sqlx::query_as(
+                "SELECT uid, kind, status, result_id, memory_task_update_id, error, enqueued_at, started_at, finished_at FROM tasks WHERE uid = $1",
             )
-            .bind(now)
-            .bind(&summary)
-            .bind(importance.map(clamp01))
-            .bind(&tags)
-            .bind(id)
-            .execute(&state.db)
-            .await;
+            .bind(task_uid)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
 
-            match pg_result {
-                Ok(r) if r.rows_affected() == 0 => {
-                    return Err((
-                        StatusCode::NOT_FOUND,
-                        Json(serde_json::json!({ "error": "Memory not found" })),
-                    ));
-                }
-                Err(e) => {
-                    error!("Failed to update PostgreSQL: {e}");
-                    return Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({ "error": "Failed to update memory" })),
-                    ));
+            Ok((StatusCode::OK, McpResponse::MemoryTaskResult { task }))
+        }
+
+        McpRequest::MemoryTaskList { limit } => {
+            let limit = limit.unwrap_or(100).clamp(1, 500);
+
+            let tasks: Vec<TaskRecord> = sqlx::query_as(
+                "SELECT uid, kind, status, result_id, memory_task_update_id, error, enqueued_at, started_at, finished_at FROM tasks ORDER BY enqueued_at DESC LIMIT $1",
+            )
+            .bind(limit as i64)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+
+            Ok((StatusCode::OK, McpResponse::MemoryTaskListResult { tasks }))
+        }
+
+        McpRequest::MemorySettingsGet => {
+            let settings = load_settings(&state.db).await?;
+            Ok((StatusCode::OK, McpResponse::MemorySettingsResult { settings }))
+        }
+
+        McpRequest::MemorySettingsUpdate {
+            searchable_fields,
+            fuzziness,
+            displayed_fields,
+            relevance_weight,
+            importance_weight,
+            recency_weight,
+            ranking_mode,
+            rrf_k,
+            rrf_signals,
+        } => {
+            let mut settings = load_settings(&state.db).await?;
+
+            if let Some(fields) = searchable_fields {
+                settings.searchable_fields = fields;
+            }
+            if let Some(f) = fuzziness {
+                settings.fuzziness = f;
+            }
+            if let Some(fields) = displayed_fields {
+                settings.displayed_fields = fields;
+            }
+            if let Some(w) = relevance_weight {
+                settings.relevance_weight = w;
+            }
+            if let Some(w) = importance_weight {
+                settings.importance_weight = w;
+            }
+            if let Some(w) = recency_weight {
+                settings.recency_weight = w;
+            }
+            if let Some(mode) = ranking_mode {
+                if mode != "linear" && mode != "rrf" {
+                    return Err(MemoryError::InvalidRankingMode);
                 }
-                _ => {}
+                settings.ranking_mode = mode;
+            }
+            if let Some(k) = rrf_k {
+                settings.rrf_k = k;
+            }
+            if let Some(signals) = rrf_signals {
+                settings.rrf_signals = signals;
             }
 
-            // 2. Update OpenSearch document
-            if let Ok(Some(mut doc)) = state.opensearch.get_document(&id.to_string()).await {
-                if let Some(c) = content {
-                    doc.content = c;
-                }
-                if let Some(s) = &summary {
-                    doc.summary = Some(s.clone());
+            save_settings(&state.db, &settings).await?;
+
+            Ok((StatusCode::OK, McpResponse::MemorySettingsResult { settings }))
+        }
+
+        McpRequest::MemoryBatch { ops } => {
+            // `memory.get` sub-ops are resolved up front via one PostgreSQL
+            // `ANY($1)` query + one OpenSearch `_mget`, rather than each
+            // issuing its own pair of round-trips through `dispatch`. Writes
+            // (save/update/delete) still go through `dispatch` individually,
+            // since those are already a single enqueue onto the task queue
+            // rather than an inline store round-trip.
+            let get_ids: Vec<Uuid> = ops
+                .iter()
+                .filter_map(|op| match op {
+                    BatchOp::MemoryGet { id } => Some(*id),
+                    _ => None,
+                })
+                .collect();
+            let fetched = fetch_memories_bulk(state, &get_ids).await;
+
+            let results: Vec<BatchOpOutcome> = stream::iter(ops.into_iter().map(|op| {
+                let state = state.clone();
+                let fetched = &fetched;
+                async move {
+                    if let BatchOp::MemoryGet { id } = op {
+                        let memory = fetched.get(&id).cloned().flatten();
+                        return BatchOpOutcome::Ok(McpResponse::MemoryGetResult { memory });
+                    }
+
+                    // Boxed to break the recursive-future type this call would
+                    // otherwise form (a batch op can itself be a `dispatch` call).
+                    let outcome = Box::pin(dispatch(&state, op.into_request())).await;
+                    match outcome {
+                        Ok((_, resp)) => BatchOpOutcome::Ok(resp),
+                        Err(e) => BatchOpOutcome::Err { message: e.message(), code: e.error_code().to_string() },
+                    }
                 }
-                if let Some(i) = importance {
-                    doc.importance_score = clamp01(i);
+            }))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+            Ok((StatusCode::OK, McpResponse::MemoryBatchResult { results }))
+        }
+
+        McpRequest::MemoryReconcile { repair, dry_run } => {
+            let report = reconcile(state, repair && !dry_run)
+                .await
+                .map_err(|e| MemoryError::IndexSyncFailed { detail: e.to_string() })?;
+
+            Ok((StatusCode::OK, McpResponse::MemoryReconcileResult { dry_run, report }))
+        }
+
+        McpRequest::TaskStatus { update_id } => {
+            let row: Option<(String, Option<bool>, Option<String>)> = sqlx::query_as(
+                "SELECT status, ok, error FROM memory_tasks WHERE update_id = $1",
+            )
+            .bind(update_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+
+            let status = match row {
+                None => MemoryTaskStatus::Unknown,
+                Some((s, ok, err)) => match s.as_str() {
+                    "enqueued" => MemoryTaskStatus::Enqueued,
+                    "processing" => MemoryTaskStatus::Processing,
+                    "processed" => MemoryTaskStatus::Processed { ok: ok.unwrap_or(false), err },
+                    _ => MemoryTaskStatus::Unknown,
+                },
+            };
+
+            Ok((StatusCode::OK, McpResponse::TaskStatusResult { status }))
+        }
+
+        McpRequest::UserSettingsGet { user_id } => {
+            let global = load_settings(&state.db).await?;
+            let overrides = load_user_settings(&state.db, &user_id).await?;
+            let settings = resolve_user_settings(&global, overrides.as_ref());
+
+            Ok((StatusCode::OK, McpResponse::UserSettingsResult { user_id, settings }))
+        }
+
+        McpRequest::UserSettingsUpdate {
+            user_id,
+            importance_weight,
+            recency_weight,
+            recency_decay_days,
+            default_limit,
+            stop_tags,
+        } => {
+            let mut raw = load_user_settings(&state.db, &user_id).await?.unwrap_or(UserSettings {
+                user_id: user_id.clone(),
+                importance_weight: None,
+                recency_weight: None,
+                recency_decay_days: None,
+                default_limit: None,
+                stop_tags: vec![],
+            });
+
+            if let Some(w) = importance_weight {
+                raw.importance_weight = Some(w);
+            }
+            if let Some(w) = recency_weight {
+                raw.recency_weight = Some(w);
+            }
+            if let Some(d) = recency_decay_days {
+                raw.recency_decay_days = Some(d);
+            }
+            if let Some(l) = default_limit {
+                raw.default_limit = Some(l);
+            }
+            if let Some(tags) = stop_tags {
+                raw.stop_tags = tags;
+            }
+
+            save_user_settings(&state.db, &raw).await?;
+
+            let global = load_settings(&state.db).await?;
+            let settings = resolve_user_settings(&global, Some(&raw));
+
+            Ok((StatusCode::OK, McpResponse::UserSettingsResult { user_id, settings }))
+        }
+    }
+}
+
+// How many sub-operations of a `memory.batch` request run concurrently.
+const BATCH_CONCURRENCY: usize = 8;
+
+// Resolves a set of memory ids to `FullMemory` in exactly one PostgreSQL
+// `WHERE id = ANY($1)` query and one OpenSearch `_mget`, instead of one
+// round-trip per id. Used by `memory.batch` to fetch every `memory.get` sub-op
+// at once. Ids with no row in either store
+// (or only one of the two, which would indicate drift — see `reconcile`) map
+// to `None`.
+async fn fetch_memories_bulk(state: &AppState, ids: &[Uuid]) -> HashMap<Uuid, Option<FullMemory>> {
+    let mut out = HashMap::with_capacity(ids.len());
+    if ids.is_empty() {
+        return out;
+    }
+
+    let indexes: Vec<MemoryIndex> = sqlx::query_as(
+        "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index WHERE id = ANY($1)",
+    )
+    .bind(ids)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let id_strings: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let docs = state.opensearch.multi_get(&id_strings).await.unwrap_or_default();
+
+    for idx in indexes {
+        let memory = docs.get(&idx.id.to_string()).map(|d| FullMemory {
+            id: idx.id,
+            user_id: idx.user_id.clone(),
+            content: d.content.clone(),
+            summary: idx.summary.clone(),
+            importance_score: idx.importance_score,
+            tags: idx.tags.clone(),
+            created_at: idx.created_at,
+            updated_at: idx.updated_at,
+        });
+        out.insert(idx.id, memory);
+    }
+
+    for id in ids {
+        out.entry(*id).or_insert(None);
+    }
+
+    out
+}
+
+// `memory_index` rows for `memory.list`'s "postgres"/"all" sources, with an
+// optional user_id and tags filter pushed down as SQL predicates rather than
+// fetched unfiltered and filtered client-side. `tags_mode` "all" uses `@>`
+// (row's tags is a superset of the filter); anything else ("any") uses `&&`
+// (row's tags overlaps the filter at all).
+async fn list_memory_index(
+    db: &PgPool,
+    user_id: Option<&str>,
+    tags: Option<&[String]>,
+    tags_mode: &str,
+    limit: i64,
+) -> Vec<MemoryIndex> {
+    let mut sql = String::from(
+        "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at FROM memory_index WHERE 1=1",
+    );
+    let mut next_param = 1;
+
+    if user_id.is_some() {
+        sql.push_str(&format!(" AND user_id = ${next_param}"));
+        next_param += 1;
+    }
+    if tags.is_some() {
+        let op = if tags_mode == "all" { "@>" } else { "&&" };
+        sql.push_str(&format!(" AND tags {op} ${next_param}"));
+        next_param += 1;
+    }
+    sql.push_str(&format!(" ORDER BY created_at DESC LIMIT ${next_param}"));
+
+    let mut query = sqlx::query_as::<_, MemoryIndex>(&sql);
+    if let Some(uid) = user_id {
+        query = query.bind(uid);
+    }
+    if let Some(t) = tags {
+        query = query.bind(t);
+    }
+    query = query.bind(limit);
+
+    query.fetch_all(db).await.unwrap_or_default()
+}
+
+// Tag -> count distribution over the same filtered set `list_memory_index`
+// would return (ignoring its LIMIT, since facets describe the whole matching
+// set, not just the current page).
+async fn tag_facets(
+    db: &PgPool,
+    user_id: Option<&str>,
+    tags: Option<&[String]>,
+    tags_mode: &str,
+    stop_tags: &[String],
+) -> HashMap<String, usize> {
+    let mut sql = String::from(
+        "SELECT unnest(tags) AS tag, COUNT(*) AS tag_count FROM memory_index WHERE 1=1",
+    );
+    let mut next_param = 1;
+
+    if user_id.is_some() {
+        sql.push_str(&format!(" AND user_id = ${next_param}"));
+        next_param += 1;
+    }
+    if tags.is_some() {
+        let op = if tags_mode == "all" { "@>" } else { "&&" };
+        sql.push_str(&format!(" AND tags {op} ${next_param}"));
+    }
+    sql.push_str(" GROUP BY tag");
+
+    let mut query = sqlx::query_as::<_, (String, i64)>(&sql);
+    if let Some(uid) = user_id {
+        query = query.bind(uid);
+    }
+    if let Some(t) = tags {
+        query = query.bind(t);
+    }
+
+    query
+        .fetch_all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(tag, count)| (tag, count as usize))
+        .filter(|(tag, _)| !stop_tags.contains(tag))
+        .collect()
+}
+
+// In-memory equivalent of `list_memory_index`'s tag predicate, for the
+// "opensearch" list source which has no `memory_index` query to push it into.
+fn tags_match(row_tags: &[String], filter: Option<&[String]>, tags_mode: &str) -> bool {
+    match filter {
+        None => true,
+        Some(filter) if tags_mode == "all" => filter.iter().all(|t| row_tags.contains(t)),
+        Some(filter) => filter.iter().any(|t| row_tags.contains(t)),
+    }
+}
+
+// In-memory equivalent of `tag_facets`, over an already-fetched page of results.
+fn count_tag_facets(results: &[ListResult], stop_tags: &[String]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for result in results {
+        for tag in &result.tags {
+            if stop_tags.contains(tag) {
+                continue;
+            }
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+// Queues a PostgreSQL + OpenSearch write for the background worker to perform
+// (see run_task_worker) instead of doing it inline on the request path.
+async fn enqueue_task(state: &AppState, uid: Uuid, kind: &str, payload: serde_json::Value) -> Result<(), MemoryError> {
+    sqlx::query("INSERT INTO tasks (uid, kind, status, payload) VALUES ($1, $2, 'enqueued', $3)")
+        .bind(uid)
+        .bind(kind)
+        .bind(payload)
+        .execute(&state.db)
+        .await
+        .map_err(|e| MemoryError::StoreUnavailable { store: "PostgreSQL", detail: e.to_string() })?;
+    Ok(())
+}
+
+struct ClaimedTask {
+    uid: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+}
+
+// What a perform_* function produced: the id `tasks.result_id` always carries,
+// plus (for memory_update/memory_delete) the `memory_tasks.update_id` of the
+// OpenSearch sync it enqueued, so a client polling memory.task.get can then
+// poll memory.task_status for that sync's own retry progress.
+struct TaskOutcome {
+    result_id: Uuid,
+    memory_task_update_id: Option<i64>,
+}
+
+impl From<Uuid> for TaskOutcome {
+    fn from(result_id: Uuid) -> Self {
+        TaskOutcome { result_id, memory_task_update_id: None }
+    }
+}
+
+async fn claim_next_task(db: &PgPool) -> anyhow::Result<Option<ClaimedTask>> {
+    let row = sqlx::query_as::<_, (Uuid, String, serde_json::Value)>(
+        r#"
+        UPDATE tasks SET status = 'processing', started_at = NOW()
+        WHERE uid = (
+            SELECT uid FROM tasks WHERE status = 'enqueued' ORDER BY enqueued_at LIMIT 1 FOR UPDATE SKIP LOCKED
+        )
+        RETURNING uid, kind, payload
+        "#,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|(uid, kind, payload)| ClaimedTask { uid, kind, payload }))
+}
+
+// Drains the `tasks` queue: claims one enqueued row at a time (via `FOR UPDATE
+// SKIP LOCKED`, so multiple server instances can run this worker concurrently
+// without double-processing a task), performs the dual write, and records the
+// outcome. Keeps the compensating OpenSearch-failure rollback that the old
+// synchronous `memory.save` path used, so the two stores still never diverge
+// silently on a transient failure.
+async fn run_task_worker(state: AppState) {
+    loop {
+        match claim_next_task(&state.db).await {
+            Ok(Some(task)) => {
+                let uid = task.uid;
+                let result: anyhow::Result<TaskOutcome> = match task.kind.as_str() {
+                    "memory_save" => perform_save(&state, &task.payload).await.map(TaskOutcome::from),
+                    "memory_update" => perform_update(&state, &task.payload).await,
+                    "memory_delete" => perform_delete(&state, &task.payload).await,
+                    "memory_import_batch" => perform_import_batch(&state, &task.payload).await.map(TaskOutcome::from),
+                    other => Err(anyhow::anyhow!("unknown task kind: {other}")),
+                };
+
+                match result {
+                    Ok(outcome) => {
+                        let _ = sqlx::query(
+                            "UPDATE tasks SET status = 'succeeded', result_id = $2, memory_task_update_id = $3, finished_at = NOW() WHERE uid = $1",
+                        )
+                        .bind(uid)
+                        .bind(outcome.result_id)
+                        .bind(outcome.memory_task_update_id)
+                        .execute(&state.db)
+                        .await;
+                    }
+                    Err(e) => {
+                        warn!("task {uid} failed: {e}");
+                        let _ = sqlx::query(
+                            "UPDATE tasks SET status = 'failed', error = $2, finished_at = NOW() WHERE uid = $1",
+                        )
+                        .bind(uid)
+                        .bind(e.to_string())
+                        .execute(&state.db)
+                        .await;
+                    }
                 }
-                if let Some(t) = &tags {
-                    doc.tags = t.clone();
+            }
+            Ok(None) => {
+                // Only sampled when idle, so the depth gauge doesn't add a
+                // query to the hot path of actually draining the queue.
+                if let Ok(depth) = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks WHERE status = 'enqueued'")
+                    .fetch_one(&state.db)
+                    .await
+                {
+                    gauge!("task_queue_depth").set(depth as f64);
                 }
-                doc.updated_at = now.to_rfc3339();
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            Err(e) => {
+                error!("task worker failed to claim a task: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+// Diffs `memory_index` against OpenSearch's documents. OpenSearch holds the
+// authoritative content, so a doc missing from Postgres is repaired by
+// re-inserting its metadata; a Postgres row missing from OpenSearch has no
+// content to recover, so repair just drops the orphaned row (same call made
+// by the dual-write rollback in `perform_save`).
+async fn reconcile(state: &AppState, repair: bool) -> anyhow::Result<ReconcileReport> {
+    let pg_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM memory_index")
+        .fetch_all(&state.db)
+        .await?;
+    let pg_set: HashSet<Uuid> = pg_ids.into_iter().collect();
+
+    let es_docs = state.opensearch.all_documents().await?;
+    let mut es_set = HashSet::with_capacity(es_docs.len());
+    let mut es_by_id = HashMap::with_capacity(es_docs.len());
+    for doc in es_docs {
+        if let Ok(id) = Uuid::parse_str(&doc.id) {
+            es_set.insert(id);
+            es_by_id.insert(id, doc);
+        }
+    }
+
+    let missing_in_opensearch: Vec<Uuid> = pg_set.difference(&es_set).copied().collect();
+    let missing_in_postgres: Vec<Uuid> = es_set.difference(&pg_set).copied().collect();
+
+    let mut reindexed_in_postgres = 0;
+    let mut deleted_orphans = 0;
+
+    if repair {
+        for id in &missing_in_postgres {
+            let Some(doc) = es_by_id.get(id) else { continue };
+            let created_at = chrono::DateTime::parse_from_rfc3339(&doc.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now());
+            let updated_at = chrono::DateTime::parse_from_rfc3339(&doc.updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or(created_at);
+
+            let inserted = sqlx::query(
+                "INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (id) DO NOTHING",
+            )
+            .bind(id)
+            .bind(&doc.user_id)
+            .bind(&doc.summary)
+            .bind(doc.importance_score)
+            .bind(&doc.tags)
+            .bind(created_at)
+            .bind(updated_at)
+            .execute(&state.db)
+            .await;
 
-                if let Err(e) = state.opensearch.index_document(&doc).await {
-                    warn!("Failed to update OpenSearch: {e}");
+            if inserted.is_ok() {
+                reindexed_in_postgres += 1;
+            }
+        }
+
+        if !missing_in_opensearch.is_empty() {
+            if let Ok(result) = sqlx::query("DELETE FROM memory_index WHERE id = ANY($1)")
+                .bind(&missing_in_opensearch)
+                .execute(&state.db)
+                .await
+            {
+                deleted_orphans = result.rows_affected() as usize;
+            }
+        }
+    }
+
+    Ok(ReconcileReport {
+        missing_in_opensearch,
+        missing_in_postgres,
+        reindexed_in_postgres,
+        deleted_orphans,
+    })
+}
+
+// Periodic report-only reconcile sweep (enabled via OPENMEMORY_RECONCILE_INTERVAL_SECS).
+// Only reports drift; repairing it is left to an explicit memory.reconcile call.
+async fn run_reconcile_sweep(state: AppState, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match reconcile(&state, false).await {
+            Ok(report) => {
+                gauge!("reconcile_missing_in_opensearch").set(report.missing_in_opensearch.len() as f64);
+                gauge!("reconcile_missing_in_postgres").set(report.missing_in_postgres.len() as f64);
+                if !report.missing_in_opensearch.is_empty() || !report.missing_in_postgres.is_empty() {
+                    warn!(
+                        missing_in_opensearch = report.missing_in_opensearch.len(),
+                        missing_in_postgres = report.missing_in_postgres.len(),
+                        "reconcile sweep found drift between PostgreSQL and OpenSearch"
+                    );
                 }
             }
+            Err(e) => error!("reconcile sweep failed: {e}"),
+        }
+    }
+}
 
-            Ok((
-                StatusCode::OK,
-                Json(McpResponse::MemoryUpdateResult { id, updated_at: now }),
-            ))
+async fn perform_save(state: &AppState, payload: &serde_json::Value) -> anyhow::Result<Uuid> {
+    let content = payload["content"].as_str().context("task payload missing content")?.to_string();
+    let summary = payload["summary"].as_str().map(|s| s.to_string());
+    let importance_score = payload["importance"].as_f64().map(|v| v as f32).unwrap_or(0.5);
+    let tags: Vec<String> = payload["tags"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+    let user_id = payload["user_id"].as_str().map(|s| s.to_string());
+
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $6)
+        "#,
+    )
+    .bind(id)
+    .bind(&user_id)
+    .bind(&summary)
+    .bind(importance_score)
+    .bind(&tags)
+    .bind(now)
+    .execute(&state.db)
+    .await
+    .context("failed to save to PostgreSQL")?;
+
+    let doc = MemoryDocument {
+        id: id.to_string(),
+        user_id,
+        content,
+        summary,
+        importance_score,
+        tags,
+        created_at: now.to_rfc3339(),
+        updated_at: now.to_rfc3339(),
+        content_codec: default_content_codec(),
+        content_length: 0,
+    };
+
+    if let Err(e) = state.opensearch.index_document(&doc).await {
+        let _ = sqlx::query("DELETE FROM memory_index WHERE id = $1").bind(id).execute(&state.db).await;
+        anyhow::bail!("failed to save to OpenSearch: {e}");
+    }
+
+    Ok(id)
+}
+
+async fn perform_update(state: &AppState, payload: &serde_json::Value) -> anyhow::Result<TaskOutcome> {
+    let id = payload["id"]
+        .as_str()
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .context("task payload missing or invalid id")?;
+    let content = payload["content"].as_str().map(|s| s.to_string());
+    let summary = payload["summary"].as_str().map(|s| s.to_string());
+    let importance = payload["importance"].as_f64().map(|v| v as f32);
+    let tags: Option<Vec<String>> = payload["tags"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect());
+
+    let now = Utc::now();
+    let result = sqlx::query(
+        "UPDATE memory_index SET updated_at = $1, summary = COALESCE($2, summary), importance_score = COALESCE($3, importance_score), tags = COALESCE($4, tags) WHERE id = $5",
+    )
+    .bind(now)
+    .bind(&summary)
+    .bind(importance)
+    .bind(&tags)
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .context("failed to update PostgreSQL")?;
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("memory {id} not found");
+    }
+
+    // The OpenSearch half is synced out-of-band by run_memory_task_worker,
+    // which retries with backoff instead of dropping the patch on failure.
+    let sync_payload = serde_json::json!({
+        "content": content,
+        "summary": summary,
+        "importance": importance,
+        "tags": tags,
+        "updated_at": now.to_rfc3339(),
+    });
+    let memory_task_update_id = match enqueue_memory_task(&state.db, id, "update", sync_payload).await {
+        Ok(update_id) => Some(update_id),
+        Err(e) => {
+            warn!("failed to enqueue OpenSearch sync for {id}: {e}");
+            None
         }
+    };
 
-        McpRequest::MemoryDelete { id } => {
-            // 1. Delete from PostgreSQL
-            let pg_result = sqlx::query("DELETE FROM memory_index WHERE id = $1")
-                .bind(id)
+    Ok(TaskOutcome { result_id: id, memory_task_update_id })
+}
+
+async fn perform_delete(state: &AppState, payload: &serde_json::Value) -> anyhow::Result<TaskOutcome> {
+    let id = payload["id"]
+        .as_str()
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .context("task payload missing or invalid id")?;
+
+    let result = sqlx::query("DELETE FROM memory_index WHERE id = $1")
+        .bind(id)
+        .execute(&state.db)
+        .await
+        .context("failed to delete from PostgreSQL")?;
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("memory {id} not found");
+    }
+
+    let memory_task_update_id = match enqueue_memory_task(&state.db, id, "delete", serde_json::json!({})).await {
+        Ok(update_id) => Some(update_id),
+        Err(e) => {
+            warn!("failed to enqueue OpenSearch sync for {id}: {e}");
+            None
+        }
+    };
+
+    Ok(TaskOutcome { result_id: id, memory_task_update_id })
+}
+
+// Enqueues the OpenSearch half of a memory.update/memory.delete for
+// run_memory_task_worker to apply, returning the monotonic `update_id`
+// memory.task_status polls.
+async fn enqueue_memory_task(db: &PgPool, memory_id: Uuid, kind: &str, payload: serde_json::Value) -> anyhow::Result<i64> {
+    let update_id: i64 = sqlx::query_scalar(
+        "INSERT INTO memory_tasks (memory_id, kind, payload) VALUES ($1, $2, $3) RETURNING update_id",
+    )
+    .bind(memory_id)
+    .bind(kind)
+    .bind(payload)
+    .fetch_one(db)
+    .await?;
+
+    Ok(update_id)
+}
+
+struct ClaimedMemoryTask {
+    update_id: i64,
+    memory_id: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+}
+
+async fn claim_next_memory_task(db: &PgPool) -> anyhow::Result<Option<ClaimedMemoryTask>> {
+    let row = sqlx::query_as::<_, (i64, Uuid, String, serde_json::Value)>(
+        r#"
+        UPDATE memory_tasks SET status = 'processing'
+        WHERE update_id = (
+            SELECT update_id FROM memory_tasks WHERE status = 'enqueued' ORDER BY update_id LIMIT 1 FOR UPDATE SKIP LOCKED
+        )
+        RETURNING update_id, memory_id, kind, payload
+        "#,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|(update_id, memory_id, kind, payload)| ClaimedMemoryTask { update_id, memory_id, kind, payload }))
+}
+
+// Applies one memory_tasks row's OpenSearch mutation.
+async fn apply_memory_task(state: &AppState, task: &ClaimedMemoryTask) -> anyhow::Result<()> {
+    match task.kind.as_str() {
+        "update" => {
+            let id = task.memory_id;
+            let Some(mut doc) = state.opensearch.get_document(&id.to_string()).await? else {
+                anyhow::bail!("document {id} missing from OpenSearch");
+            };
+            if let Some(c) = task.payload["content"].as_str() {
+                doc.content = c.to_string();
+            }
+            if let Some(s) = task.payload["summary"].as_str() {
+                doc.summary = Some(s.to_string());
+            }
+            if let Some(i) = task.payload["importance"].as_f64() {
+                doc.importance_score = clamp01(i as f32);
+            }
+            if let Some(tags) = task.payload["tags"].as_array() {
+                doc.tags = tags.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect();
+            }
+            if let Some(u) = task.payload["updated_at"].as_str() {
+                doc.updated_at = u.to_string();
+            }
+            state.opensearch.index_document(&doc).await
+        }
+        "delete" => state.opensearch.delete_document(&task.memory_id.to_string()).await.map(|_| ()),
+        other => anyhow::bail!("unknown memory task kind: {other}"),
+    }
+}
+
+const MEMORY_TASK_MAX_ATTEMPTS: u32 = 5;
+
+// Delay before retry number `attempts` (1-based) of a memory_tasks row.
+fn memory_task_backoff_delay(attempts: u32) -> Duration {
+    Duration::from_millis(200 * 2u64.pow(attempts))
+}
+
+// Calls `attempt` up to `max_attempts` times, sleeping with exponential
+// backoff between failures, stopping as soon as one succeeds. Returns the
+// number of attempts made and the outcome of the last one.
+async fn retry_with_backoff<F, Fut>(max_attempts: u32, mut attempt: F) -> (u32, Result<(), String>)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let mut attempts = 0;
+    let mut outcome: Result<(), String> = Err("not attempted".to_string());
+
+    while attempts < max_attempts {
+        attempts += 1;
+        outcome = attempt().await;
+        if outcome.is_ok() {
+            break;
+        }
+        if attempts < max_attempts {
+            tokio::time::sleep(memory_task_backoff_delay(attempts)).await;
+        }
+    }
+
+    (attempts, outcome)
+}
+
+// Drains `memory_tasks`, retrying each claimed row with exponential backoff
+// before recording it `processed` (ok or failed) for memory.task_status.
+async fn run_memory_task_worker(state: AppState) {
+    loop {
+        match claim_next_memory_task(&state.db).await {
+            Ok(Some(task)) => {
+                let (attempts, outcome) = retry_with_backoff(MEMORY_TASK_MAX_ATTEMPTS, || async {
+                    apply_memory_task(&state, &task).await.map_err(|e| e.to_string())
+                })
+                .await;
+
+                let (ok, error) = match &outcome {
+                    Ok(()) => (true, None),
+                    Err(e) => {
+                        warn!("memory task {} ({}) failed after {attempts} attempts: {e}", task.update_id, task.kind);
+                        (false, Some(e.clone()))
+                    }
+                };
+
+                let _ = sqlx::query(
+                    "UPDATE memory_tasks SET status = 'processed', ok = $2, error = $3, attempts = $4, updated_at = NOW() WHERE update_id = $1",
+                )
+                .bind(task.update_id)
+                .bind(ok)
+                .bind(error)
+                .bind(attempts as i32)
                 .execute(&state.db)
                 .await;
+            }
+            Ok(None) => tokio::time::sleep(Duration::from_millis(200)).await,
+            Err(e) => {
+                error!("memory task worker failed to claim a task: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
 
-            let deleted = match pg_result {
-                Ok(r) => r.rows_affected() > 0,
-                Err(e) => {
-                    error!("Failed to delete from PostgreSQL: {e}");
-                    return Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({ "error": "Failed to delete memory" })),
-                    ));
+// One record of a bulk `/import` request, in either NDJSON (one JSON object
+// per line) or CSV (header row `content,summary,importance,tags,user_id`,
+// `tags` pipe-separated) form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImportRecord {
+    content: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    importance: Option<f32>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    user_id: Option<String>,
+}
+
+// Splits one CSV line into fields, honoring RFC 4180 double-quoting so a
+// `content` field containing a comma (or a quote, escaped as `""`) doesn't
+// get mis-split into the wrong number of columns.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
                 }
-            };
+            } else {
+                field.push(c);
+            }
+        } else {
+            match c {
+                '"' if field.is_empty() => in_quotes = true,
+                ',' => {
+                    fields.push(std::mem::take(&mut field));
+                }
+                _ => field.push(c),
+            }
+        }
+    }
+    fields.push(field);
+
+    fields
+}
 
-            if !deleted {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(serde_json::json!({ "error": "Memory not found" })),
-                ));
+fn parse_csv_record(header: &[String], line: &str) -> Result<ImportRecord, String> {
+    let fields = split_csv_line(line);
+    if fields.len() != header.len() {
+        return Err(format!("expected {} columns, got {}", header.len(), fields.len()));
+    }
+
+    let mut content = None;
+    let mut summary = None;
+    let mut importance = None;
+    let mut tags = None;
+    let mut user_id = None;
+
+    for (col, value) in header.iter().zip(fields.iter()) {
+        let value = value.trim();
+        match col.as_str() {
+            "content" => content = Some(value.to_string()),
+            "summary" if !value.is_empty() => summary = Some(value.to_string()),
+            "importance" if !value.is_empty() => {
+                importance = Some(value.parse::<f32>().map_err(|e| format!("invalid importance: {e}"))?)
             }
+            "tags" if !value.is_empty() => tags = Some(value.split('|').map(|t| t.to_string()).collect()),
+            "user_id" if !value.is_empty() => user_id = Some(value.to_string()),
+            _ => {}
+        }
+    }
 
-            // 2. Delete from OpenSearch
-            let _ = state.opensearch.delete_document(&id.to_string()).await;
+    Ok(ImportRecord {
+        content: content.ok_or_else(|| "missing content column".to_string())?,
+        summary,
+        importance,
+        tags,
+        user_id,
+    })
+}
 
-            Ok((
-                StatusCode::OK,
-                Json(McpResponse::MemoryDeleteResult { id, deleted: true }),
-            ))
+const IMPORT_BATCH_SIZE: usize = 500;
+
+// Flushes a batch of records: one multi-value PostgreSQL INSERT plus one
+// OpenSearch `_bulk` request, instead of per-document round-trips. Returns
+// the number of records indexed in both stores and appends per-line failures
+// (including partial per-document OpenSearch failures) to `failed`.
+async fn flush_import_batch(
+    state: &AppState,
+    batch: &mut Vec<(usize, ImportRecord)>,
+    failed: &mut Vec<serde_json::Value>,
+) -> usize {
+    if batch.is_empty() {
+        return 0;
+    }
+
+    let now = Utc::now();
+    let ids: Vec<Uuid> = batch.iter().map(|_| Uuid::new_v4()).collect();
+    let importances: Vec<f32> = batch.iter().map(|(_, r)| r.importance.map(clamp01).unwrap_or(0.5)).collect();
+    let tags: Vec<Vec<String>> = batch.iter().map(|(_, r)| r.tags.clone().unwrap_or_default()).collect();
+
+    // Multi-value INSERT built for this batch's size: one round-trip per batch
+    // instead of per-record, and each row's own `$n` placeholders so `tags`
+    // arrays don't need to share a rectangular shape the way UNNEST would require.
+    let mut query_str = String::from(
+        "INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at) VALUES ",
+    );
+    for i in 0..batch.len() {
+        if i > 0 {
+            query_str.push(',');
+        }
+        let base = i * 6;
+        query_str.push_str(&format!(
+            "(${}, ${}, ${}, ${}, ${}, ${}, ${})",
+            base + 1, base + 2, base + 3, base + 4, base + 5, base + 6, base + 6
+        ));
+    }
+
+    let mut query = sqlx::query(&query_str);
+    for (i, (_, record)) in batch.iter().enumerate() {
+        query = query
+            .bind(ids[i])
+            .bind(record.user_id.clone())
+            .bind(record.summary.clone())
+            .bind(importances[i])
+            .bind(tags[i].clone())
+            .bind(now);
+    }
+    let pg_result = query.execute(&state.db).await;
+
+    if let Err(e) = pg_result {
+        error!("Failed to batch-insert import records into PostgreSQL: {e}");
+        for (line, _) in batch.drain(..) {
+            failed.push(serde_json::json!({ "line": line, "error": format!("PostgreSQL insert failed: {e}") }));
+        }
+        return 0;
+    }
+
+    let docs: Vec<MemoryDocument> = batch
+        .iter()
+        .zip(ids.iter())
+        .zip(importances.iter())
+        .zip(tags.iter())
+        .map(|(((r, id), importance), tags)| MemoryDocument {
+            id: id.to_string(),
+            user_id: r.1.user_id.clone(),
+            content: r.1.content.clone(),
+            summary: r.1.summary.clone(),
+            importance_score: *importance,
+            tags: tags.clone(),
+            created_at: now.to_rfc3339(),
+            updated_at: now.to_rfc3339(),
+            content_codec: default_content_codec(),
+            content_length: 0,
+        })
+        .collect();
+
+    let mut indexed = 0;
+    match state.opensearch.bulk_index(&docs).await {
+        Ok(results) => {
+            for ((line, _), result) in batch.iter().zip(results.iter()) {
+                match result {
+                    Ok(()) => indexed += 1,
+                    Err(e) => failed.push(serde_json::json!({ "line": line, "error": format!("OpenSearch index failed: {e}") })),
+                }
+            }
+        }
+        Err(e) => {
+            error!("Failed to bulk-index import records into OpenSearch: {e}");
+            for (line, _) in batch.iter() {
+                failed.push(serde_json::json!({ "line": line, "error": format!("OpenSearch bulk index failed: {e}") }));
+            }
+        }
+    }
+
+    batch.clear();
+    indexed
+}
+
+// Performs one `memory_import_batch` task, off the request thread: the same
+// dual write `flush_import_batch` always did, just run by `run_task_worker`
+// instead of inline in `import_memories` so a slow OpenSearch bulk index
+// doesn't block the upload request. Per-record failures are logged rather
+// than surfaced to a caller, since nothing is left listening by then.
+async fn perform_import_batch(state: &AppState, payload: &serde_json::Value) -> anyhow::Result<Uuid> {
+    let batch_id = payload["batch_id"]
+        .as_str()
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .context("import batch payload missing batch_id")?;
+
+    let mut batch: Vec<(usize, ImportRecord)> = payload["records"]
+        .as_array()
+        .context("import batch payload missing records")?
+        .iter()
+        .filter_map(|r| {
+            let line = r["line"].as_u64()? as usize;
+            let record: ImportRecord = serde_json::from_value(r["record"].clone()).ok()?;
+            Some((line, record))
+        })
+        .collect();
+
+    let mut failed = Vec::new();
+    let indexed = flush_import_batch(state, &mut batch, &mut failed).await;
+    if !failed.is_empty() {
+        warn!("import batch {batch_id}: {indexed} indexed, {} failed: {:?}", failed.len(), failed);
+    }
+
+    Ok(batch_id)
+}
+
+// Bulk import endpoint: NDJSON (default) or CSV (`X-Memory-Import-Format: csv`
+// header) body, optionally `Content-Encoding: gzip|zstd|br` compressed,
+// decoded and parsed as a stream rather than buffered whole so large corpora
+// don't need to fit in memory at once.
+async fn import_memories(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<impl IntoResponse, MemoryError> {
+    let is_csv = headers
+        .get("x-memory-import-format")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false);
+
+    let byte_stream = body.into_data_stream().map_err(std::io::Error::other);
+    let stream_reader = StreamReader::new(byte_stream);
+
+    let encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("identity")
+        .to_string();
+
+    let mut reader: Box<dyn AsyncBufRead + Unpin + Send> = match encoding.as_str() {
+        "gzip" | "gz" => Box::new(BufReader::new(GzipDecoder::new(BufReader::new(stream_reader)))),
+        "zstd" => Box::new(BufReader::new(ZstdDecoder::new(BufReader::new(stream_reader)))),
+        "br" => Box::new(BufReader::new(BrotliDecoder::new(BufReader::new(stream_reader)))),
+        _ => Box::new(BufReader::new(stream_reader)),
+    };
+
+    let mut received = 0usize;
+    let mut failed: Vec<serde_json::Value> = Vec::new();
+    let mut batch: Vec<(usize, ImportRecord)> = Vec::new();
+    let mut batch_ids: Vec<Uuid> = Vec::new();
+    let mut csv_header: Option<Vec<String>> = None;
+
+    loop {
+        let mut line = String::new();
+        let bytes_read = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| MemoryError::StoreUnavailable { store: "import stream", detail: e.to_string() })?;
+        if bytes_read == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.is_empty() {
+            continue;
+        }
+
+        if is_csv && csv_header.is_none() {
+            csv_header = Some(split_csv_line(line).iter().map(|c| c.trim().to_string()).collect());
+            continue;
         }
+
+        received += 1;
+        let record = if is_csv {
+            parse_csv_record(csv_header.as_deref().unwrap_or_default(), line)
+        } else {
+            serde_json::from_str::<ImportRecord>(line).map_err(|e| e.to_string())
+        };
+
+        match record {
+            Ok(r) => batch.push((received, r)),
+            Err(e) => failed.push(serde_json::json!({ "line": received, "error": e })),
+        }
+
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            batch_ids.push(enqueue_import_batch(&state, &mut batch).await?);
+        }
+    }
+
+    if !batch.is_empty() {
+        batch_ids.push(enqueue_import_batch(&state, &mut batch).await?);
     }
+
+    info!("import: {received} received, {} batches enqueued, {} parse failures", batch_ids.len(), failed.len());
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "received": received,
+            "batches_enqueued": batch_ids.len(),
+            "task_uids": batch_ids,
+            "failed": failed,
+        })),
+    ))
 }
 
-fn compute_combined_score(importance: f32, created_at: DateTime<Utc>) -> f32 {
-    let recency = recency_score(created_at);
-    // OpenSearch handles keyword relevance, we add importance + recency
-    (importance * 0.6) + (recency * 0.4)
+// Enqueues one `memory_import_batch` task for `run_task_worker`/
+// `perform_import_batch` to apply, same as `memory.save`/`update`/`delete`
+// queue their own dual write instead of performing it on the request thread.
+// Drains `batch` and returns its task uid.
+async fn enqueue_import_batch(state: &AppState, batch: &mut Vec<(usize, ImportRecord)>) -> Result<Uuid, MemoryError> {
+    let batch_id = Uuid::new_v4();
+    let records: Vec<serde_json::Value> = batch
+        .drain(..)
+        .map(|(line, record)| serde_json::json!({ "line": line, "record": record }))
+        .collect();
+    let payload = serde_json::json!({ "batch_id": batch_id, "records": records });
+    enqueue_task(state, batch_id, "memory_import_batch", payload).await?;
+    Ok(batch_id)
 }
 
-fn recency_score(created_at: DateTime<Utc>) -> f32 {
+// `importance_weight`/`recency_weight`/`recency_decay_days` are the resolved
+// per-user values (see `resolve_user_settings`) so each user's ranking policy
+// can differ; `relevance_weight` stays deployment-wide, from `MemorySettings`.
+fn compute_combined_score(
+    relevance: f32,
+    importance: f32,
+    created_at: DateTime<Utc>,
+    relevance_weight: f32,
+    importance_weight: f32,
+    recency_weight: f32,
+    recency_decay_days: f32,
+) -> f32 {
+    let recency = recency_score(created_at, recency_decay_days);
+    (relevance * relevance_weight) + (importance * importance_weight) + (recency * recency_weight)
+}
+
+fn recency_score(created_at: DateTime<Utc>, decay_days: f32) -> f32 {
     let age = Utc::now().signed_duration_since(created_at);
     let age_days = age.num_seconds().max(0) as f32 / (60.0 * 60.0 * 24.0);
-    (-age_days / 30.0).exp().clamp(0.0, 1.0)
+    (-age_days / decay_days.max(0.01)).exp().clamp(0.0, 1.0)
+}
+
+// Reciprocal Rank Fusion: rank the candidate set independently by each of
+// `settings.rrf_signals` (rank 1 = best), then score each candidate as
+// `sum over signals of 1/(k + rank)`. Only each signal's relative order
+// matters, not its raw magnitude, so this combines BM25 relevance,
+// importance, and recency without needing them on a comparable scale.
+fn rrf_scores(candidates: &[RankedCandidate], settings: &MemorySettings) -> HashMap<Uuid, f32> {
+    let mut fused: HashMap<Uuid, f32> = candidates.iter().map(|c| (c.id, 0.0)).collect();
+
+    for signal in &settings.rrf_signals {
+        let key: fn(&RankedCandidate) -> f32 = match signal.as_str() {
+            "relevance" => |c| c.relevance,
+            "importance" => |c| c.importance,
+            "recency" => |c| c.recency,
+            _ => continue,
+        };
+
+        let mut ranked: Vec<&RankedCandidate> = candidates.iter().collect();
+        ranked.sort_by(|a, b| key(b).partial_cmp(&key(a)).unwrap_or(Ordering::Equal));
+
+        for (i, candidate) in ranked.into_iter().enumerate() {
+            let rank = (i + 1) as f32;
+            if let Some(score) = fused.get_mut(&candidate.id) {
+                *score += 1.0 / (settings.rrf_k + rank);
+            }
+        }
+    }
+
+    fused
 }
 
 fn clamp01(v: f32) -> f32 {
     v.clamp(0.0, 1.0)
 }
 
+// Shared by `OpenSearchClient::compress_for_storage`/`decompress_doc`. Kept as
+// free functions (rather than methods) since they only need a codec name, not
+// any client state.
+async fn compress_bytes(codec: &str, data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt, BufReader};
+
+    let cursor = BufReader::new(std::io::Cursor::new(data));
+    let mut out = Vec::new();
+    match codec {
+        "zstd" => {
+            ZstdEncoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        "gzip" | "gz" => {
+            GzipEncoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        "br" => {
+            BrotliEncoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        other => anyhow::bail!("unsupported content codec: {other}"),
+    }
+    Ok(out)
+}
+
+async fn decompress_bytes(codec: &str, data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt, BufReader};
+
+    let cursor = BufReader::new(std::io::Cursor::new(data));
+    let mut out = Vec::new();
+    match codec {
+        "zstd" => {
+            ZstdDecoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        "gzip" | "gz" => {
+            GzipDecoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        "br" => {
+            BrotliDecoder::new(cursor).read_to_end(&mut out).await?;
+        }
+        other => anyhow::bail!("unknown content codec: {other}"),
+    }
+    Ok(out)
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_decode(s: &str) -> anyhow::Result<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        anyhow::bail!("invalid hex content: odd length");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| anyhow::anyhow!("invalid hex content: {e}")))
+        .collect()
+}
+
 #[allow(dead_code)]
 async fn _sleep_for_readability() {
     tokio::time::sleep(Duration::from_millis(10)).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn memory_task_backoff_delay_grows_exponentially() {
+        assert_eq!(memory_task_backoff_delay(1), Duration::from_millis(400));
+        assert_eq!(memory_task_backoff_delay(2), Duration::from_millis(800));
+        assert_eq!(memory_task_backoff_delay(3), Duration::from_millis(1600));
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_stops_as_soon_as_an_attempt_succeeds() {
+        let (attempts, outcome) = retry_with_backoff(MEMORY_TASK_MAX_ATTEMPTS, || async { Ok(()) }).await;
+        assert_eq!(attempts, 1);
+        assert_eq!(outcome, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_retries_a_transient_failure_then_succeeds() {
+        let calls = Cell::new(0);
+        let (attempts, outcome) = retry_with_backoff(3, || {
+            let n = calls.get();
+            calls.set(n + 1);
+            async move { if n == 0 { Err("transient".to_string()) } else { Ok(()) } }
+        })
+        .await;
+        assert_eq!(attempts, 2);
+        assert_eq!(outcome, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_gives_up_after_max_attempts() {
+        let calls = Cell::new(0);
+        let (attempts, outcome) = retry_with_backoff(2, || {
+            calls.set(calls.get() + 1);
+            async { Err("still broken".to_string()) }
+        })
+        .await;
+        assert_eq!(attempts, 2);
+        assert_eq!(calls.get(), 2);
+        assert_eq!(outcome, Err("still broken".to_string()));
+    }
+
+    #[test]
+    fn split_csv_line_handles_quoted_commas_and_escaped_quotes() {
+        let fields = split_csv_line(r#"hello,"a, b","She said ""hi""",plain"#);
+        assert_eq!(fields, vec!["hello", "a, b", r#"She said "hi""#, "plain"]);
+    }
+}
@@ -1,10 +1,25 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
 use anyhow::{Context, Result};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{get, post},
+    Json, Router,
+};
 use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, StreamExt};
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::{postgres::PgPoolOptions, PgPool, FromRow};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
@@ -30,8 +45,156 @@ struct JsonRpcResponse {
 struct JsonRpcError {
     code: i32,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<ErrorData>,
+}
+
+// Extra, machine-readable detail carried in `error.data` so MCP clients can
+// branch on `error_code` instead of parsing `message` prose.
+#[derive(Debug, Serialize)]
+struct ErrorData {
+    error_code: &'static str,
+    error_type: &'static str,
+    status: u16,
+    error_link: String,
+}
+
+/// Semantic error kinds surfaced by the MCP tool handlers. Each kind carries
+/// its own JSON-RPC numeric code, a stable `error_code` string, an
+/// `error_type` category, and an HTTP-style status hint.
+#[derive(Debug, Clone, Copy)]
+enum ErrorKind {
+    MissingContent,
+    MissingQuery,
+    MissingItems,
+    MissingFilter,
+    MissingId,
+    InvalidImportance,
+    InvalidCausalityToken,
+    OpenSearchUnavailable,
+    PostgresUnavailable,
+    MemoryNotFound,
+    ParseError,
+    UnknownTool,
+    MethodNotFound,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ErrorType {
+    InvalidRequest,
+    NotFound,
+    Internal,
+}
+
+impl ErrorType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorType::InvalidRequest => "invalid_request",
+            ErrorType::NotFound => "not_found",
+            ErrorType::Internal => "internal",
+        }
+    }
+}
+
+impl ErrorKind {
+    fn error_code(&self) -> &'static str {
+        match self {
+            ErrorKind::MissingContent => "missing_content",
+            ErrorKind::MissingQuery => "missing_query",
+            ErrorKind::MissingItems => "missing_items",
+            ErrorKind::MissingFilter => "missing_filter",
+            ErrorKind::MissingId => "missing_id",
+            ErrorKind::InvalidImportance => "invalid_importance",
+            ErrorKind::InvalidCausalityToken => "invalid_causality_token",
+            ErrorKind::OpenSearchUnavailable => "opensearch_unavailable",
+            ErrorKind::PostgresUnavailable => "postgres_unavailable",
+            ErrorKind::MemoryNotFound => "memory_not_found",
+            ErrorKind::ParseError => "parse_error",
+            ErrorKind::UnknownTool => "unknown_tool",
+            ErrorKind::MethodNotFound => "method_not_found",
+        }
+    }
+
+    fn error_type(&self) -> ErrorType {
+        match self {
+            ErrorKind::MissingContent
+            | ErrorKind::MissingQuery
+            | ErrorKind::MissingItems
+            | ErrorKind::MissingFilter
+            | ErrorKind::MissingId
+            | ErrorKind::InvalidImportance
+            | ErrorKind::InvalidCausalityToken
+            | ErrorKind::ParseError
+            | ErrorKind::UnknownTool
+            | ErrorKind::MethodNotFound => ErrorType::InvalidRequest,
+            ErrorKind::MemoryNotFound => ErrorType::NotFound,
+            ErrorKind::OpenSearchUnavailable | ErrorKind::PostgresUnavailable => ErrorType::Internal,
+        }
+    }
+
+    // JSON-RPC 2.0 reserves -32700..-32600 for protocol-level errors; map our
+    // semantic kinds onto that space rather than collapsing everything to -32603.
+    fn rpc_code(&self) -> i32 {
+        match self {
+            ErrorKind::ParseError => -32700,
+            ErrorKind::UnknownTool | ErrorKind::MethodNotFound => -32601,
+            ErrorKind::MissingContent
+            | ErrorKind::MissingQuery
+            | ErrorKind::MissingItems
+            | ErrorKind::MissingFilter
+            | ErrorKind::MissingId
+            | ErrorKind::InvalidImportance
+            | ErrorKind::InvalidCausalityToken
+            | ErrorKind::MemoryNotFound => -32602,
+            ErrorKind::OpenSearchUnavailable | ErrorKind::PostgresUnavailable => -32603,
+        }
+    }
+
+    fn status_hint(&self) -> u16 {
+        match self.error_type() {
+            ErrorType::InvalidRequest => 400,
+            ErrorType::NotFound => 404,
+            ErrorType::Internal => 500,
+        }
+    }
+
+    fn error_link(&self) -> String {
+        format!("https://docs.openmemory.dev/errors#{}", self.error_code())
+    }
+
+    fn data(&self) -> ErrorData {
+        ErrorData {
+            error_code: self.error_code(),
+            error_type: self.error_type().as_str(),
+            status: self.status_hint(),
+            error_link: self.error_link(),
+        }
+    }
+}
+
+/// A typed MCP error. Carries an [`ErrorKind`] plus a human-readable message;
+/// `handle_request` downcasts `anyhow::Error` back to this to populate
+/// `error.data` for the client.
+#[derive(Debug)]
+struct McpError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl McpError {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+}
+
+impl std::fmt::Display for McpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
 }
 
+impl std::error::Error for McpError {}
+
 // PostgreSQL: Index data
 #[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
 struct MemoryIndex {
@@ -42,6 +205,15 @@ struct MemoryIndex {
     tags: Vec<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    // `memory_key` groups the causal versions/siblings of one logical memory;
+    // `version` is that row's vector clock; `superseded` marks rows a later,
+    // dominating write has replaced. Absent on older queries that don't select them.
+    #[sqlx(default)]
+    memory_key: Uuid,
+    #[sqlx(default)]
+    version: serde_json::Value,
+    #[sqlx(default)]
+    superseded: bool,
 }
 
 // OpenSearch: Full content
@@ -68,22 +240,231 @@ struct SearchResult {
     score: f32,
 }
 
+// Per-deployment tuning for indexing/search, persisted in `memory_settings`.
+// Modeled on MeiliSearch's searchable/displayed-attributes settings model so
+// relevance can be tuned without recompiling.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+struct MemorySettings {
+    // [[field, boost], ...], e.g. [["content", 2.0], ["summary", 1.0]]
+    searchable_fields: serde_json::Value,
+    fuzziness: bool,
+    displayed_fields: Vec<String>,
+    importance_weight: f32,
+    recency_weight: f32,
+    recency_half_life_days: f32,
+    // Ordered tie-breakers applied lexicographically: earlier rules dominate,
+    // later rules only break ties left by earlier ones.
+    ranking_rules: Vec<String>,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            searchable_fields: json!([["content", 2.0], ["summary", 1.0], ["tags", 1.0]]),
+            fuzziness: true,
+            displayed_fields: vec![
+                "content".to_string(),
+                "summary".to_string(),
+                "tags".to_string(),
+                "importance_score".to_string(),
+                "created_at".to_string(),
+            ],
+            importance_weight: 0.6,
+            recency_weight: 0.4,
+            recency_half_life_days: 30.0,
+            ranking_rules: vec![
+                "words".to_string(),
+                "typo".to_string(),
+                "proximity".to_string(),
+                "attribute".to_string(),
+                "exactness".to_string(),
+                "importance".to_string(),
+                "recency".to_string(),
+            ],
+        }
+    }
+}
+
+impl MemorySettings {
+    // OpenSearch `multi_match` field list with `^boost` suffixes, e.g. `content^2`.
+    fn opensearch_fields(&self) -> Vec<String> {
+        self.searchable_fields
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|entry| {
+                        let pair = entry.as_array()?;
+                        let field = pair.first()?.as_str()?;
+                        let boost = pair.get(1).and_then(|b| b.as_f64()).unwrap_or(1.0);
+                        Some(format!("{field}^{boost}"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_else(|| MemorySettings::default().opensearch_fields())
+    }
+}
+
+// Vector clock: writer id -> counter. Tracks causality across concurrent
+// `memory_update` calls so the server can tell a proper update from a
+// concurrent sibling write, Garage K2V-style.
+type VersionVector = std::collections::BTreeMap<String, i64>;
+
+// `a` dominates `b` (i.e. `a` causally follows `b`) when it is at least as
+// advanced as `b` on every writer and strictly ahead on at least one.
+fn vector_dominates(a: &VersionVector, b: &VersionVector) -> bool {
+    let keys: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    let mut strictly_greater = false;
+    for k in keys {
+        let av = a.get(k).copied().unwrap_or(0);
+        let bv = b.get(k).copied().unwrap_or(0);
+        if av < bv {
+            return false;
+        }
+        if av > bv {
+            strictly_greater = true;
+        }
+    }
+    strictly_greater
+}
+
+fn vector_max(a: &VersionVector, b: &VersionVector) -> VersionVector {
+    let mut out = a.clone();
+    for (k, v) in b {
+        let entry = out.entry(k.clone()).or_insert(0);
+        if *v > *entry {
+            *entry = *v;
+        }
+    }
+    out
+}
+
+// The causality token is just the vector clock serialized as JSON; clients
+// treat it as opaque and pass it back unmodified on the next memory_update.
+fn encode_causality_token(version: &VersionVector) -> String {
+    serde_json::to_string(version).unwrap_or_else(|_| "{}".to_string())
+}
+
+fn decode_causality_token(token: &str) -> Result<VersionVector> {
+    serde_json::from_str(token)
+        .map_err(|e| McpError::new(ErrorKind::InvalidCausalityToken, format!("invalid causality token: {e}")).into())
+}
+
+// Counters and one gauge exposed at `/metrics` in Prometheus text format.
+// Latency and result counts are exported as cumulative sums next to the call
+// totals; there are no histogram buckets. Plain atomics rather than a metrics-crate registry, matching the rest of
+// this file's preference for hand-rolled state over pulling in a framework
+// for a single observability surface.
+#[derive(Default)]
+struct Metrics {
+    memory_save_total: std::sync::atomic::AtomicU64,
+    memory_save_errors_total: std::sync::atomic::AtomicU64,
+    memory_save_duration_ms_sum: std::sync::atomic::AtomicU64,
+    memory_search_total: std::sync::atomic::AtomicU64,
+    memory_search_errors_total: std::sync::atomic::AtomicU64,
+    memory_search_duration_ms_sum: std::sync::atomic::AtomicU64,
+    memory_search_result_count_sum: std::sync::atomic::AtomicU64,
+    opensearch_errors_total: std::sync::atomic::AtomicU64,
+    postgres_errors_total: std::sync::atomic::AtomicU64,
+}
+
+impl Metrics {
+    fn record_save(&self, duration: std::time::Duration, ok: bool) {
+        use std::sync::atomic::Ordering::Relaxed;
+        self.memory_save_total.fetch_add(1, Relaxed);
+        self.memory_save_duration_ms_sum.fetch_add(duration.as_millis() as u64, Relaxed);
+        if !ok {
+            self.memory_save_errors_total.fetch_add(1, Relaxed);
+        }
+    }
+
+    fn record_search(&self, duration: std::time::Duration, result_count: usize, ok: bool) {
+        use std::sync::atomic::Ordering::Relaxed;
+        self.memory_search_total.fetch_add(1, Relaxed);
+        self.memory_search_duration_ms_sum.fetch_add(duration.as_millis() as u64, Relaxed);
+        self.memory_search_result_count_sum.fetch_add(result_count as u64, Relaxed);
+        if !ok {
+            self.memory_search_errors_total.fetch_add(1, Relaxed);
+        }
+    }
+
+    fn record_opensearch_error(&self) {
+        self.opensearch_errors_total.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn record_postgres_error(&self) {
+        self.postgres_errors_total.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn render(&self, indexed_documents: i64) -> String {
+        use std::sync::atomic::Ordering::Relaxed;
+        let mut out = String::new();
+        out.push_str("# HELP openmemory_memory_save_total Total memory_save calls.\n");
+        out.push_str("# TYPE openmemory_memory_save_total counter\n");
+        out.push_str(&format!("openmemory_memory_save_total {}\n", self.memory_save_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_save_errors_total Total memory_save calls that failed.\n");
+        out.push_str("# TYPE openmemory_memory_save_errors_total counter\n");
+        out.push_str(&format!("openmemory_memory_save_errors_total {}\n", self.memory_save_errors_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_save_duration_ms_sum Cumulative memory_save latency in milliseconds.\n");
+        out.push_str("# TYPE openmemory_memory_save_duration_ms_sum counter\n");
+        out.push_str(&format!("openmemory_memory_save_duration_ms_sum {}\n", self.memory_save_duration_ms_sum.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_search_total Total memory_search calls.\n");
+        out.push_str("# TYPE openmemory_memory_search_total counter\n");
+        out.push_str(&format!("openmemory_memory_search_total {}\n", self.memory_search_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_search_errors_total Total memory_search calls that failed.\n");
+        out.push_str("# TYPE openmemory_memory_search_errors_total counter\n");
+        out.push_str(&format!("openmemory_memory_search_errors_total {}\n", self.memory_search_errors_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_search_duration_ms_sum Cumulative memory_search latency in milliseconds.\n");
+        out.push_str("# TYPE openmemory_memory_search_duration_ms_sum counter\n");
+        out.push_str(&format!("openmemory_memory_search_duration_ms_sum {}\n", self.memory_search_duration_ms_sum.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_memory_search_result_count_sum Cumulative number of results returned by memory_search.\n");
+        out.push_str("# TYPE openmemory_memory_search_result_count_sum counter\n");
+        out.push_str(&format!("openmemory_memory_search_result_count_sum {}\n", self.memory_search_result_count_sum.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_opensearch_errors_total OpenSearch index and search requests that returned a non-success status (other OpenSearch calls and transport errors are not counted).\n");
+        out.push_str("# TYPE openmemory_opensearch_errors_total counter\n");
+        out.push_str(&format!("openmemory_opensearch_errors_total {}\n", self.opensearch_errors_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_postgres_errors_total Failed PostgreSQL inserts in memory_save (other PostgreSQL queries are not counted).\n");
+        out.push_str("# TYPE openmemory_postgres_errors_total counter\n");
+        out.push_str(&format!("openmemory_postgres_errors_total {}\n", self.postgres_errors_total.load(Relaxed)));
+
+        out.push_str("# HELP openmemory_indexed_documents Current number of live (non-superseded) indexed memories.\n");
+        out.push_str("# TYPE openmemory_indexed_documents gauge\n");
+        out.push_str(&format!("openmemory_indexed_documents {}\n", indexed_documents));
+
+        out
+    }
+}
+
 #[derive(Clone)]
 struct OpenSearchClient {
     client: HttpClient,
     base_url: String,
     index: String,
+    metrics: Arc<Metrics>,
 }
 
 impl OpenSearchClient {
-    fn new(base_url: &str) -> Self {
+    fn new(base_url: &str, metrics: Arc<Metrics>) -> Self {
         Self {
             client: HttpClient::new(),
             base_url: base_url.trim_end_matches('/').to_string(),
             index: "memories".to_string(),
+            metrics,
         }
     }
 
+    async fn ping(&self) -> bool {
+        let url = format!("{}/{}", self.base_url, self.index);
+        matches!(self.client.head(&url).send().await, Ok(resp) if resp.status().is_success())
+    }
+
     async fn create_index(&self) -> Result<()> {
         let url = format!("{}/{}", self.base_url, self.index);
 
@@ -126,13 +507,14 @@ impl OpenSearchClient {
         let resp = self.client.put(&url).json(doc).send().await?;
         if !resp.status().is_success() {
             let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to index document: {}", body);
+            self.metrics.record_opensearch_error();
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("failed to index document: {body}")).into());
         }
 
         Ok(())
     }
 
-    async fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryDocument>> {
+    async fn search(&self, query: &str, limit: usize, settings: &MemorySettings) -> Result<Vec<MemoryDocument>> {
         let url = format!("{}/{}/_search", self.base_url, self.index);
 
         let search_body = json!({
@@ -140,8 +522,8 @@ impl OpenSearchClient {
             "query": {
                 "multi_match": {
                     "query": query,
-                    "fields": ["content^2", "summary", "tags"],
-                    "fuzziness": "AUTO"
+                    "fields": settings.opensearch_fields(),
+                    "fuzziness": if settings.fuzziness { "AUTO" } else { "0" }
                 }
             },
             "_source": true
@@ -150,7 +532,8 @@ impl OpenSearchClient {
         let resp = self.client.post(&url).json(&search_body).send().await?;
         if !resp.status().is_success() {
             let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Search failed: {}", body);
+            self.metrics.record_opensearch_error();
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("search failed: {body}")).into());
         }
 
         let result: serde_json::Value = resp.json().await?;
@@ -166,11 +549,133 @@ impl OpenSearchClient {
 
         Ok(docs)
     }
+
+    async fn get_document(&self, id: &str) -> Result<Option<MemoryDocument>> {
+        let url = format!("{}/{}/_doc/{}", self.base_url, self.index, id);
+
+        let resp = self.client.get(&url).send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("failed to get document: {body}")).into());
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let doc: MemoryDocument = serde_json::from_value(result["_source"].clone())?;
+        Ok(Some(doc))
+    }
+
+    // Indexes many documents in a single request via the `_bulk` API, returning
+    // a per-document outcome so a save_batch caller can isolate partial failures.
+    async fn bulk_index(&self, docs: &[MemoryDocument]) -> Result<Vec<BulkItemResult>> {
+        if docs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let url = format!("{}/_bulk", self.base_url);
+        let mut body = String::new();
+        for doc in docs {
+            body.push_str(&serde_json::to_string(&json!({ "index": { "_index": self.index, "_id": doc.id } }))?);
+            body.push('\n');
+            body.push_str(&serde_json::to_string(doc)?);
+            body.push('\n');
+        }
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("bulk index failed: {text}")).into());
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let items = result["items"].as_array().cloned().unwrap_or_default();
+
+        Ok(docs
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| match items.get(i) {
+                Some(item) => {
+                    let status = item["index"]["status"].as_u64().unwrap_or(500);
+                    BulkItemResult {
+                        id: doc.id.clone(),
+                        ok: (200..300).contains(&status),
+                        error: item["index"]["error"]["reason"].as_str().map(|s| s.to_string()),
+                    }
+                }
+                None => BulkItemResult { id: doc.id.clone(), ok: false, error: Some("missing bulk response item".to_string()) },
+            })
+            .collect())
+    }
+
+    // Deletes many documents in a single request via the `_bulk` API.
+    async fn bulk_delete(&self, ids: &[String]) -> Result<Vec<BulkItemResult>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let url = format!("{}/_bulk", self.base_url);
+        let mut body = String::new();
+        for id in ids {
+            body.push_str(&serde_json::to_string(&json!({ "delete": { "_index": self.index, "_id": id } }))?);
+            body.push('\n');
+        }
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("bulk delete failed: {text}")).into());
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let items = result["items"].as_array().cloned().unwrap_or_default();
+
+        Ok(ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| match items.get(i) {
+                Some(item) => {
+                    let status = item["delete"]["status"].as_u64().unwrap_or(500);
+                    BulkItemResult {
+                        id: id.clone(),
+                        ok: (200..300).contains(&status) || status == 404,
+                        error: item["delete"]["error"]["reason"].as_str().map(|s| s.to_string()),
+                    }
+                }
+                None => BulkItemResult { id: id.clone(), ok: false, error: Some("missing bulk response item".to_string()) },
+            })
+            .collect())
+    }
+}
+
+// Per-document outcome of a `_bulk` request.
+#[derive(Debug, Clone, Serialize)]
+struct BulkItemResult {
+    id: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 struct McpServer {
     db: PgPool,
     opensearch: OpenSearchClient,
+    metrics: Arc<Metrics>,
 }
 
 impl McpServer {
@@ -197,7 +702,10 @@ impl McpServer {
                 importance_score REAL NOT NULL DEFAULT 0.5,
                 tags TEXT[] NOT NULL DEFAULT '{}',
                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                memory_key UUID NOT NULL,
+                version JSONB NOT NULL DEFAULT '{}',
+                superseded BOOLEAN NOT NULL DEFAULT FALSE
             )
             "#,
         )
@@ -205,15 +713,104 @@ impl McpServer {
         .await
         .context("failed to create memory_index table")?;
 
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_memory_index_memory_key ON memory_index(memory_key)")
+            .execute(&db)
+            .await
+            .ok();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS memory_settings (
+                id SMALLINT PRIMARY KEY DEFAULT 1,
+                searchable_fields JSONB NOT NULL,
+                fuzziness BOOLEAN NOT NULL,
+                displayed_fields TEXT[] NOT NULL,
+                importance_weight REAL NOT NULL,
+                recency_weight REAL NOT NULL,
+                recency_half_life_days REAL NOT NULL,
+                ranking_rules TEXT[] NOT NULL,
+                CONSTRAINT memory_settings_singleton CHECK (id = 1)
+            )
+            "#,
+        )
+        .execute(&db)
+        .await
+        .context("failed to create memory_settings table")?;
+
+        let metrics = Arc::new(Metrics::default());
+
         // OpenSearch connection
         let opensearch_url = std::env::var("OPENSEARCH_URL")
             .unwrap_or_else(|_| "http://localhost:9200".to_string());
 
-        let opensearch = OpenSearchClient::new(&opensearch_url);
+        let opensearch = OpenSearchClient::new(&opensearch_url, metrics.clone());
         opensearch.create_index().await?;
         info!("connected to OpenSearch");
 
-        Ok(Self { db, opensearch })
+        Ok(Self { db, opensearch, metrics })
+    }
+
+    // `/health` readiness check: pings PostgreSQL with `SELECT 1` and OpenSearch
+    // with the same `HEAD` used by `OpenSearchClient::create_index`.
+    async fn is_healthy(&self) -> (bool, bool) {
+        let postgres_ok = sqlx::query("SELECT 1").execute(&self.db).await.is_ok();
+        let opensearch_ok = self.opensearch.ping().await;
+        (postgres_ok, opensearch_ok)
+    }
+
+    async fn indexed_document_count(&self) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM memory_index WHERE superseded = false")
+            .fetch_one(&self.db)
+            .await
+            .unwrap_or(0)
+    }
+
+    // Reads the singleton settings row, seeding it with defaults on first use.
+    async fn load_settings(&self) -> Result<MemorySettings> {
+        let row: Option<MemorySettings> = sqlx::query_as(
+            "SELECT searchable_fields, fuzziness, displayed_fields, importance_weight, recency_weight, recency_half_life_days, ranking_rules FROM memory_settings WHERE id = 1",
+        )
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to load settings: {e}")))?;
+
+        match row {
+            Some(settings) => Ok(settings),
+            None => {
+                let defaults = MemorySettings::default();
+                self.save_settings(&defaults).await?;
+                Ok(defaults)
+            }
+        }
+    }
+
+    async fn save_settings(&self, settings: &MemorySettings) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO memory_settings (id, searchable_fields, fuzziness, displayed_fields, importance_weight, recency_weight, recency_half_life_days, ranking_rules)
+            VALUES (1, $1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                searchable_fields = EXCLUDED.searchable_fields,
+                fuzziness = EXCLUDED.fuzziness,
+                displayed_fields = EXCLUDED.displayed_fields,
+                importance_weight = EXCLUDED.importance_weight,
+                recency_weight = EXCLUDED.recency_weight,
+                recency_half_life_days = EXCLUDED.recency_half_life_days,
+                ranking_rules = EXCLUDED.ranking_rules
+            "#,
+        )
+        .bind(&settings.searchable_fields)
+        .bind(settings.fuzziness)
+        .bind(&settings.displayed_fields)
+        .bind(settings.importance_weight)
+        .bind(settings.recency_weight)
+        .bind(settings.recency_half_life_days)
+        .bind(&settings.ranking_rules)
+        .execute(&self.db)
+        .await
+        .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to save settings: {e}")))?;
+
+        Ok(())
     }
 
     async fn handle_request(&mut self, req: JsonRpcRequest) -> JsonRpcResponse {
@@ -221,7 +818,7 @@ impl McpServer {
             "initialize" => self.handle_initialize().await,
             "tools/list" => self.handle_tools_list().await,
             "tools/call" => self.handle_tools_call(req.params).await,
-            _ => Err(anyhow::anyhow!("method not found: {}", req.method)),
+            _ => Err(McpError::new(ErrorKind::MethodNotFound, format!("method not found: {}", req.method)).into()),
         };
 
         match result {
@@ -235,10 +832,24 @@ impl McpServer {
                 jsonrpc: "2.0".to_string(),
                 id: req.id,
                 result: None,
-                error: Some(JsonRpcError {
-                    code: -32603,
-                    message: e.to_string(),
-                }),
+                error: Some(Self::to_json_rpc_error(e)),
+            },
+        }
+    }
+
+    // Downcasts to our typed `McpError` when the failure originated from a
+    // known tool-handling path, otherwise falls back to a generic internal error.
+    fn to_json_rpc_error(e: anyhow::Error) -> JsonRpcError {
+        match e.downcast_ref::<McpError>() {
+            Some(mcp_err) => JsonRpcError {
+                code: mcp_err.kind.rpc_code(),
+                message: mcp_err.message.clone(),
+                data: Some(mcp_err.kind.data()),
+            },
+            None => JsonRpcError {
+                code: -32603,
+                message: e.to_string(),
+                data: None,
             },
         }
     }
@@ -303,6 +914,127 @@ impl McpServer {
                         },
                         "required": ["query"]
                     }
+                },
+                {
+                    "name": "memory_configure",
+                    "description": "Update search/ranking settings: searchable field boosts, fuzziness, displayed fields, and importance/recency weights",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "searchable_fields": {
+                                "type": "array",
+                                "description": "[[field, boost], ...] e.g. [[\"content\", 2.0], [\"summary\", 1.0]]"
+                            },
+                            "fuzziness": {
+                                "type": "boolean",
+                                "description": "Whether to allow fuzzy (typo-tolerant) matching"
+                            },
+                            "displayed_fields": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "Fields to list as displayed; stored and returned by memory_get_settings, but memory_search output is not filtered by it"
+                            },
+                            "importance_weight": {
+                                "type": "number",
+                                "description": "Weight of importance in the combined score (default 0.6)"
+                            },
+                            "recency_weight": {
+                                "type": "number",
+                                "description": "Weight of recency in the combined score (default 0.4)"
+                            },
+                            "recency_half_life_days": {
+                                "type": "number",
+                                "description": "Number of days for the recency score to decay by half (default 30)"
+                            },
+                            "ranking_rules": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "Ordered tie-breaker rules: words, typo, proximity, attribute, exactness, importance, recency"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "memory_get_settings",
+                    "description": "Return the current search/ranking settings",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "memory_save_batch",
+                    "description": "Save many memories in one request; each item succeeds or fails independently",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "items": {
+                                "type": "array",
+                                "description": "Array of objects shaped like memory_save's arguments",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "content": {"type": "string"},
+                                        "summary": {"type": "string"},
+                                        "importance": {"type": "number"},
+                                        "tags": {"type": "array", "items": {"type": "string"}}
+                                    },
+                                    "required": ["content"]
+                                }
+                            }
+                        },
+                        "required": ["items"]
+                    }
+                },
+                {
+                    "name": "memory_delete_batch",
+                    "description": "Delete many memories by explicit id list or by a tags/query filter",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "ids": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "Explicit memory ids to delete"
+                            },
+                            "filter": {
+                                "type": "object",
+                                "description": "Alternative to `ids`: { tags: [...], query: \"...\" } (matches are unioned)",
+                                "properties": {
+                                    "tags": {"type": "array", "items": {"type": "string"}},
+                                    "query": {"type": "string"}
+                                }
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "memory_update",
+                    "description": "Update a memory. Pass the causality_token from the last memory_save/memory_get/memory_update so concurrent edits are detected instead of silently overwritten",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": "string", "description": "Memory id returned by memory_save"},
+                            "content": {"type": "string"},
+                            "summary": {"type": "string"},
+                            "importance": {"type": "number"},
+                            "tags": {"type": "array", "items": {"type": "string"}},
+                            "causality_token": {"type": "string", "description": "Token from the last read of this memory"},
+                            "writer_id": {"type": "string", "description": "Identifies this writer in the version vector (default \"default\")"}
+                        },
+                        "required": ["id", "content"]
+                    }
+                },
+                {
+                    "name": "memory_get",
+                    "description": "Fetch a memory by id, returning all live sibling versions (if a concurrent update produced more than one) plus a fresh causality_token",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": "string"}
+                        },
+                        "required": ["id"]
+                    }
                 }
             ]
         }))
@@ -316,28 +1048,451 @@ impl McpServer {
         match name {
             "memory_save" => self.memory_save(arguments).await,
             "memory_search" => self.memory_search(arguments).await,
-            _ => Err(anyhow::anyhow!("unknown tool: {}", name)),
+            "memory_configure" => self.memory_configure(arguments).await,
+            "memory_get_settings" => self.memory_get_settings().await,
+            "memory_save_batch" => self.memory_save_batch(arguments).await,
+            "memory_delete_batch" => self.memory_delete_batch(arguments).await,
+            "memory_update" => self.memory_update(arguments).await,
+            "memory_get" => self.memory_get(arguments).await,
+            _ => Err(McpError::new(ErrorKind::UnknownTool, format!("unknown tool: {}", name)).into()),
         }
     }
 
-    async fn memory_save(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
-        let content = args["content"].as_str().context("missing content")?.to_string();
-        let summary = args["summary"].as_str().map(|s| s.to_string());
-        let importance = args["importance"].as_f64().unwrap_or(0.5) as f32;
-        let importance = importance.clamp(0.0, 1.0);
-        let tags: Vec<String> = args["tags"]
+    async fn memory_save_batch(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let items = args["items"]
             .as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-            .unwrap_or_default();
+            .ok_or_else(|| McpError::new(ErrorKind::MissingItems, "missing items array"))?;
+
+        struct PendingItem {
+            id: Uuid,
+            content: String,
+            summary: Option<String>,
+            importance: f32,
+            tags: Vec<String>,
+        }
+
+        let mut pending: Vec<(usize, PendingItem)> = Vec::new();
+        let mut outcomes: Vec<serde_json::Value> = vec![serde_json::Value::Null; items.len()];
+
+        for (idx, item) in items.iter().enumerate() {
+            let Some(content) = item["content"].as_str() else {
+                outcomes[idx] = json!({ "ok": false, "error_code": ErrorKind::MissingContent.error_code(), "error": "missing content" });
+                continue;
+            };
+            let summary = item["summary"].as_str().map(|s| s.to_string());
+            let importance = item["importance"].as_f64().unwrap_or(0.5).clamp(0.0, 1.0) as f32;
+            let tags: Vec<String> = item["tags"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+
+            pending.push((
+                idx,
+                PendingItem { id: Uuid::new_v4(), content: content.to_string(), summary, importance, tags },
+            ));
+        }
+
+        if pending.is_empty() {
+            return Ok(json!({
+                "content": [{ "type": "text", "text": serde_json::to_string_pretty(&outcomes).unwrap_or_default() }]
+            }));
+        }
 
-        let id = Uuid::new_v4();
         let now = Utc::now();
 
-        // 1. Save index to PostgreSQL
+        // 1. One single-row INSERT per item, all inside a single transaction;
+        // the rows are not combined into one multi-row statement.
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to start transaction: {e}")))?;
+
+        for (_, item) in &pending {
+            // `memory_key` equals the row id itself for a brand-new memory,
+            // same convention as memory_save.
+            sqlx::query(
+                r#"
+                INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at, memory_key)
+                VALUES ($1, $2, $3, $4, $5, $6, $6, $1)
+                "#,
+            )
+            .bind(item.id)
+            .bind(None::<String>)
+            .bind(&item.summary)
+            .bind(item.importance)
+            .bind(&item.tags)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to save batch to PostgreSQL: {e}")))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to commit batch: {e}")))?;
+
+        // 2. OpenSearch bulk index.
+        let docs: Vec<MemoryDocument> = pending
+            .iter()
+            .map(|(_, item)| MemoryDocument {
+                id: item.id.to_string(),
+                user_id: None,
+                content: item.content.clone(),
+                summary: item.summary.clone(),
+                importance_score: item.importance,
+                tags: item.tags.clone(),
+                created_at: now.to_rfc3339(),
+                updated_at: now.to_rfc3339(),
+            })
+            .collect();
+
+        let bulk_results = self.opensearch.bulk_index(&docs).await.unwrap_or_else(|e| {
+            warn!("bulk index failed, marking all items failed: {e}");
+            docs.iter().map(|d| BulkItemResult { id: d.id.clone(), ok: false, error: Some(e.to_string()) }).collect()
+        });
+
+        // Roll back the PostgreSQL rows whose OpenSearch write failed so the two
+        // stores don't silently drift, mirroring memory_save's rollback discipline.
+        let failed_ids: Vec<Uuid> = bulk_results
+            .iter()
+            .zip(pending.iter())
+            .filter(|(result, _)| !result.ok)
+            .map(|(_, (_, item))| item.id)
+            .collect();
+
+        if !failed_ids.is_empty() {
+            let _ = sqlx::query("DELETE FROM memory_index WHERE id = ANY($1)")
+                .bind(&failed_ids)
+                .execute(&self.db)
+                .await;
+        }
+
+        for (result, (idx, item)) in bulk_results.iter().zip(pending.iter()) {
+            outcomes[*idx] = if result.ok {
+                json!({ "ok": true, "id": item.id })
+            } else {
+                json!({
+                    "ok": false,
+                    "error_code": ErrorKind::OpenSearchUnavailable.error_code(),
+                    "error": result.error.clone().unwrap_or_default(),
+                })
+            };
+        }
+
+        info!("saved {}/{} memories in batch", bulk_results.iter().filter(|r| r.ok).count(), items.len());
+
+        Ok(json!({
+            "content": [{ "type": "text", "text": serde_json::to_string_pretty(&outcomes).unwrap_or_default() }]
+        }))
+    }
+
+    async fn memory_delete_batch(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let ids: Vec<Uuid> = if let Some(arr) = args["ids"].as_array() {
+            arr.iter().filter_map(|v| v.as_str().and_then(|s| Uuid::parse_str(s).ok())).collect()
+        } else if let Some(filter) = args["filter"].as_object() {
+            let tags: Vec<String> = filter
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+            let query = filter.get("query").and_then(|v| v.as_str());
+
+            let mut resolved: Vec<Uuid> = Vec::new();
+            if !tags.is_empty() {
+                let rows: Vec<(Uuid,)> = sqlx::query_as("SELECT id FROM memory_index WHERE tags && $1")
+                    .bind(&tags)
+                    .fetch_all(&self.db)
+                    .await
+                    .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to resolve tag filter: {e}")))?;
+                resolved.extend(rows.into_iter().map(|(id,)| id));
+            }
+            if let Some(q) = query {
+                let settings = self.load_settings().await?;
+                let docs = self.opensearch.search(q, 200, &settings).await.unwrap_or_default();
+                resolved.extend(docs.iter().filter_map(|d| Uuid::parse_str(&d.id).ok()));
+            }
+            resolved.sort();
+            resolved.dedup();
+            resolved
+        } else {
+            return Err(McpError::new(ErrorKind::MissingFilter, "must provide either `ids` or `filter`").into());
+        };
+
+        if ids.is_empty() {
+            return Ok(json!({ "content": [{ "type": "text", "text": "Deleted 0 memories" }] }));
+        }
+
+        let pg_result = sqlx::query("DELETE FROM memory_index WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&self.db)
+            .await
+            .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to delete batch from PostgreSQL: {e}")))?;
+
+        let id_strings: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let bulk_results = self.opensearch.bulk_delete(&id_strings).await.unwrap_or_else(|e| {
+            warn!("bulk delete failed, marking all items failed: {e}");
+            id_strings.iter().map(|id| BulkItemResult { id: id.clone(), ok: false, error: Some(e.to_string()) }).collect()
+        });
+        let opensearch_failures = bulk_results.iter().filter(|r| !r.ok).count();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": format!(
+                    "Deleted {} memories ({} OpenSearch deletions failed and will need reconciliation)",
+                    pg_result.rows_affected(),
+                    opensearch_failures,
+                )
+            }]
+        }))
+    }
+
+    // Applies an update under the causality token from the caller's last read.
+    // If that token is causally current the update supersedes every prior live
+    // version; if a concurrent write happened, the stored siblings it can't
+    // prove it followed are kept alongside the new write instead of overwritten.
+    async fn memory_update(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let memory_key = args["id"]
+            .as_str()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .ok_or_else(|| McpError::new(ErrorKind::MissingId, "missing or invalid id"))?;
+        let content = args["content"]
+            .as_str()
+            .ok_or_else(|| McpError::new(ErrorKind::MissingContent, "missing content"))?
+            .to_string();
+        let writer_id = args["writer_id"].as_str().unwrap_or("default").to_string();
+        let incoming_version: VersionVector = match args["causality_token"].as_str() {
+            Some(token) => decode_causality_token(token)?,
+            None => VersionVector::new(),
+        };
+
+        let live: Vec<MemoryIndex> = sqlx::query_as(
+            "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at, memory_key, version, superseded FROM memory_index WHERE memory_key = $1 AND superseded = false",
+        )
+        .bind(memory_key)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to load memory: {e}")))?;
+
+        if live.is_empty() {
+            return Err(McpError::new(ErrorKind::MemoryNotFound, format!("memory {memory_key} not found")).into());
+        }
+
+        let mut new_version = incoming_version;
+        *new_version.entry(writer_id).or_insert(0) += 1;
+
+        // Reuse the oldest live sibling's metadata for fields the caller didn't override.
+        let base = live.iter().min_by_key(|i| i.created_at).expect("live is non-empty");
+        let summary = args["summary"].as_str().map(|s| s.to_string()).or_else(|| base.summary.clone());
+        let importance = args["importance"].as_f64().map(|v| v.clamp(0.0, 1.0) as f32).unwrap_or(base.importance_score);
+        let tags: Vec<String> = args["tags"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_else(|| base.tags.clone());
+
+        let new_row_id = Uuid::new_v4();
+        let now = Utc::now();
+        let new_version_json = serde_json::to_value(&new_version).unwrap_or_default();
+
         sqlx::query(
             r#"
-            INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at, memory_key, version, superseded)
+            VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, false)
+            "#,
+        )
+        .bind(new_row_id)
+        .bind(None::<String>)
+        .bind(&summary)
+        .bind(importance)
+        .bind(&tags)
+        .bind(now)
+        .bind(memory_key)
+        .bind(&new_version_json)
+        .execute(&self.db)
+        .await
+        .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to save update to PostgreSQL: {e}")))?;
+
+        let doc = MemoryDocument {
+            id: new_row_id.to_string(),
+            user_id: None,
+            content: content.clone(),
+            summary: summary.clone(),
+            importance_score: importance,
+            tags: tags.clone(),
+            created_at: now.to_rfc3339(),
+            updated_at: now.to_rfc3339(),
+        };
+        if let Err(e) = self.opensearch.index_document(&doc).await {
+            let _ = sqlx::query("DELETE FROM memory_index WHERE id = $1").bind(new_row_id).execute(&self.db).await;
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("failed to save update to OpenSearch: {e}")).into());
+        }
+
+        // Supersede every live sibling the new write causally dominates; anything
+        // it doesn't dominate stays live as a sibling for the client to resolve.
+        // The superseded row's OpenSearch document is dropped too, so it stops
+        // showing up in memory_search as a duplicate of the memory it was replaced by.
+        let mut still_live_versions = vec![new_version.clone()];
+        let mut superseded_ids = Vec::new();
+        for sibling in &live {
+            let sibling_version: VersionVector = serde_json::from_value(sibling.version.clone()).unwrap_or_default();
+            if vector_dominates(&new_version, &sibling_version) {
+                let _ = sqlx::query("UPDATE memory_index SET superseded = true WHERE id = $1").bind(sibling.id).execute(&self.db).await;
+                superseded_ids.push(sibling.id.to_string());
+            } else {
+                still_live_versions.push(sibling_version);
+            }
+        }
+        if !superseded_ids.is_empty() {
+            if let Err(e) = self.opensearch.bulk_delete(&superseded_ids).await {
+                warn!("failed to remove superseded OpenSearch documents for {memory_key}: {e}");
+            }
+        }
+
+        let fresh_token = still_live_versions.into_iter().reduce(|a, b| vector_max(&a, &b)).unwrap_or(new_version);
+
+        info!("updated memory {} (new version row {})", memory_key, new_row_id);
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": format!(
+                    "Updated memory {}\nNew version id: {}\nCausality token: {}",
+                    memory_key, new_row_id, encode_causality_token(&fresh_token)
+                )
+            }]
+        }))
+    }
+
+    async fn memory_get(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let memory_key = args["id"]
+            .as_str()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .ok_or_else(|| McpError::new(ErrorKind::MissingId, "missing or invalid id"))?;
+
+        let live: Vec<MemoryIndex> = sqlx::query_as(
+            "SELECT id, user_id, summary, importance_score, tags, created_at, updated_at, memory_key, version, superseded FROM memory_index WHERE memory_key = $1 AND superseded = false",
+        )
+        .bind(memory_key)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| McpError::new(ErrorKind::PostgresUnavailable, format!("failed to load memory: {e}")))?;
+
+        if live.is_empty() {
+            return Err(McpError::new(ErrorKind::MemoryNotFound, format!("memory {memory_key} not found")).into());
+        }
+
+        let mut siblings = Vec::with_capacity(live.len());
+        let mut versions = Vec::with_capacity(live.len());
+        for row in &live {
+            let content = self.opensearch.get_document(&row.id.to_string()).await.ok().flatten().map(|d| d.content);
+            versions.push(serde_json::from_value(row.version.clone()).unwrap_or_default());
+            siblings.push(json!({
+                "version_id": row.id,
+                "content": content,
+                "summary": row.summary,
+                "importance_score": row.importance_score,
+                "tags": row.tags,
+                "updated_at": row.updated_at,
+            }));
+        }
+
+        let fresh_token: VersionVector = versions.into_iter().reduce(|a: VersionVector, b: VersionVector| vector_max(&a, &b)).unwrap_or_default();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&json!({
+                    "id": memory_key,
+                    "siblings": siblings,
+                    "causality_token": encode_causality_token(&fresh_token),
+                })).unwrap_or_default()
+            }]
+        }))
+    }
+
+    async fn memory_configure(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let mut settings = self.load_settings().await?;
+
+        if let Some(fields) = args["searchable_fields"].as_array() {
+            settings.searchable_fields = serde_json::Value::Array(fields.clone());
+        }
+        if let Some(f) = args["fuzziness"].as_bool() {
+            settings.fuzziness = f;
+        }
+        if let Some(fields) = args["displayed_fields"].as_array() {
+            settings.displayed_fields = fields.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect();
+        }
+        if let Some(w) = args["importance_weight"].as_f64() {
+            settings.importance_weight = w as f32;
+        }
+        if let Some(w) = args["recency_weight"].as_f64() {
+            settings.recency_weight = w as f32;
+        }
+        if let Some(h) = args["recency_half_life_days"].as_f64() {
+            settings.recency_half_life_days = h as f32;
+        }
+        if let Some(rules) = args["ranking_rules"].as_array() {
+            settings.ranking_rules = rules.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect();
+        }
+
+        self.save_settings(&settings).await?;
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": format!("Settings updated:\n{}", serde_json::to_string_pretty(&settings).unwrap_or_default())
+            }]
+        }))
+    }
+
+    async fn memory_get_settings(&mut self) -> Result<serde_json::Value> {
+        let settings = self.load_settings().await?;
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&settings).unwrap_or_default()
+            }]
+        }))
+    }
+
+    async fn memory_save(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let started = std::time::Instant::now();
+        let result = self.memory_save_inner(args).await;
+        self.metrics.record_save(started.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn memory_save_inner(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let content = args["content"]
+            .as_str()
+            .ok_or_else(|| McpError::new(ErrorKind::MissingContent, "missing content"))?
+            .to_string();
+        let summary = args["summary"].as_str().map(|s| s.to_string());
+        if !args["importance"].is_null() && args["importance"].as_f64().is_none() {
+            return Err(McpError::new(ErrorKind::InvalidImportance, "importance must be a number between 0.0 and 1.0").into());
+        }
+        let importance = args["importance"].as_f64().unwrap_or(0.5) as f32;
+        let importance = importance.clamp(0.0, 1.0);
+        let tags: Vec<String> = args["tags"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+        let writer_id = args["writer_id"].as_str().unwrap_or("default").to_string();
+
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let version: VersionVector = [(writer_id, 1i64)].into_iter().collect();
+        let version_json = serde_json::to_value(&version).unwrap_or_default();
+
+        // 1. Save index to PostgreSQL. `memory_key` is the logical identity clients
+        // use for future memory_update/memory_get calls; for a brand-new memory it
+        // equals the row id itself.
+        sqlx::query(
+            r#"
+            INSERT INTO memory_index (id, user_id, summary, importance_score, tags, created_at, updated_at, memory_key, version)
+            VALUES ($1, $2, $3, $4, $5, $6, $6, $1, $7)
             "#,
         )
         .bind(id)
@@ -346,9 +1501,13 @@ impl McpServer {
         .bind(importance)
         .bind(&tags)
         .bind(now)
+        .bind(&version_json)
         .execute(&self.db)
         .await
-        .context("failed to save to PostgreSQL")?;
+        .map_err(|e| {
+            self.metrics.record_postgres_error();
+            McpError::new(ErrorKind::PostgresUnavailable, format!("failed to save to PostgreSQL: {e}"))
+        })?;
 
         // 2. Save full document to OpenSearch
         let doc = MemoryDocument {
@@ -368,7 +1527,7 @@ impl McpServer {
                 .bind(id)
                 .execute(&self.db)
                 .await;
-            return Err(e);
+            return Err(McpError::new(ErrorKind::OpenSearchUnavailable, format!("failed to save to OpenSearch: {e}")).into());
         }
 
         info!("saved memory {} to PostgreSQL + OpenSearch", id);
@@ -376,19 +1535,38 @@ impl McpServer {
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": format!("Saved memory with ID: {}\nContent: {}\nSummary: {}\nTags: {:?}\nImportance: {:.1}",
-                    id, content, summary.as_deref().unwrap_or("-"), tags, importance)
+                "text": format!(
+                    "Saved memory with ID: {}\nContent: {}\nSummary: {}\nTags: {:?}\nImportance: {:.1}\nCausality token: {}",
+                    id, content, summary.as_deref().unwrap_or("-"), tags, importance, encode_causality_token(&version)
+                )
             }]
         }))
     }
 
     async fn memory_search(&mut self, args: &serde_json::Value) -> Result<serde_json::Value> {
-        let query = args["query"].as_str().context("missing query")?.to_string();
+        let started = std::time::Instant::now();
+        let result = self.memory_search_inner(args).await;
+        let result_count = result.as_ref().map(|(_, count)| *count).unwrap_or(0);
+        self.metrics.record_search(started.elapsed(), result_count, result.is_ok());
+        result.map(|(value, _)| value)
+    }
+
+    async fn memory_search_inner(&mut self, args: &serde_json::Value) -> Result<(serde_json::Value, usize)> {
+        let query = args["query"]
+            .as_str()
+            .ok_or_else(|| McpError::new(ErrorKind::MissingQuery, "missing query"))?
+            .to_string();
         let limit = args["limit"].as_u64().unwrap_or(5) as usize;
         let limit = limit.clamp(1, 20);
 
+        let settings = self.load_settings().await?;
+
         // Search in OpenSearch
-        let docs = self.opensearch.search(&query, limit * 2).await.unwrap_or_default();
+        let docs = self
+            .opensearch
+            .search(&query, limit * 2, &settings)
+            .await
+            .map_err(|e| McpError::new(ErrorKind::OpenSearchUnavailable, format!("search failed: {e}")))?;
 
         // Get importance scores from PostgreSQL
         let ids: Vec<Uuid> = docs
@@ -408,30 +1586,46 @@ impl McpServer {
             vec![]
         };
 
-        // Combine and score
-        let mut results: Vec<SearchResult> = docs
+        // Combine, then rank via the ordered ranking-rules pipeline (ties broken
+        // left to right by `settings.ranking_rules`).
+        let mut results: Vec<(SearchResult, RankingCriteria)> = docs
             .iter()
             .filter_map(|doc| {
                 let id = Uuid::parse_str(&doc.id).ok()?;
                 let index = index_data.iter().find(|i| i.id == id);
                 let importance = index.map(|i| i.importance_score).unwrap_or(0.5);
                 let created_at = index.map(|i| i.created_at).unwrap_or_else(Utc::now);
-                let score = compute_combined_score(importance, created_at);
-
-                Some(SearchResult {
-                    id,
-                    content: doc.content.clone(),
-                    summary: doc.summary.clone(),
-                    tags: doc.tags.clone(),
-                    importance_score: importance,
-                    created_at,
-                    score,
-                })
+                let score = compute_combined_score(importance, created_at, &settings);
+                let criteria = compute_ranking_criteria(&query, doc, importance, created_at, &settings);
+
+                Some((
+                    SearchResult {
+                        id,
+                        content: doc.content.clone(),
+                        summary: doc.summary.clone(),
+                        tags: doc.tags.clone(),
+                        importance_score: importance,
+                        created_at,
+                        score,
+                    },
+                    criteria,
+                ))
             })
             .collect();
 
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.sort_by(|(_, a), (_, b)| {
+            for rule in &settings.ranking_rules {
+                match a.compare_by(b, rule) {
+                    std::cmp::Ordering::Equal => continue,
+                    other => return other.reverse(),
+                }
+            }
+            std::cmp::Ordering::Equal
+        });
+
+        let mut results: Vec<SearchResult> = results.into_iter().map(|(r, _)| r).collect();
         results.truncate(limit);
+        let result_count = results.len();
 
         // Format output
         let mut text = format!("Found {} results for: \"{}\"\n\n", results.len(), query);
@@ -453,39 +1647,266 @@ impl McpServer {
             }
         }
 
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": text
-            }]
-        }))
+        Ok((
+            json!({
+                "content": [{
+                    "type": "text",
+                    "text": text
+                }]
+            }),
+            result_count,
+        ))
     }
 }
 
-fn compute_combined_score(importance: f32, created_at: DateTime<Utc>) -> f32 {
-    let recency = recency_score(created_at);
-    (importance * 0.6) + (recency * 0.4)
+// Per-candidate criteria for the ranking-rules pipeline, compared lexicographically
+// in the order given by `MemorySettings::ranking_rules`.
+#[derive(Debug, Clone, Copy)]
+struct RankingCriteria {
+    words: i32,      // distinct query terms matched (higher is better)
+    typo: i32,       // total edit distance of matched terms (lower is better)
+    proximity: i32,  // sum of gaps between matched term positions in content (lower is better)
+    attribute: i32,  // best field a term matched in: content=0, summary=1, tags=2 (lower is better)
+    exactness: i32,  // count of exact (non-fuzzy) term matches (higher is better)
+    importance: f32, // higher is better
+    recency: f32,    // higher is better
 }
 
-fn recency_score(created_at: DateTime<Utc>) -> f32 {
+impl RankingCriteria {
+    // Ordering such that Greater means `self` should sort before `other`.
+    fn compare_by(&self, other: &Self, rule: &str) -> std::cmp::Ordering {
+        match rule {
+            "words" => self.words.cmp(&other.words),
+            "typo" => other.typo.cmp(&self.typo),
+            "proximity" => other.proximity.cmp(&self.proximity),
+            "attribute" => other.attribute.cmp(&self.attribute),
+            "exactness" => self.exactness.cmp(&other.exactness),
+            "importance" => self.importance.partial_cmp(&other.importance).unwrap_or(std::cmp::Ordering::Equal),
+            "recency" => self.recency.partial_cmp(&other.recency).unwrap_or(std::cmp::Ordering::Equal),
+            _ => std::cmp::Ordering::Equal,
+        }
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+// Recomputes words/typo/proximity/attribute/exactness against `doc` for `query`,
+// tolerating up to 2 edits per term to mirror OpenSearch's `fuzziness: AUTO`.
+fn compute_ranking_criteria(
+    query: &str,
+    doc: &MemoryDocument,
+    importance: f32,
+    created_at: DateTime<Utc>,
+    settings: &MemorySettings,
+) -> RankingCriteria {
+    let terms: Vec<String> = query.to_lowercase().split_whitespace().map(|s| s.to_string()).collect();
+    let summary_lower = doc.summary.clone().unwrap_or_default().to_lowercase();
+
+    // field rank: content=0 (best), summary=1, tags=2
+    let fields: [(i32, Vec<String>); 3] = [
+        (0, doc.content.to_lowercase().split_whitespace().map(|s| s.to_string()).collect()),
+        (1, summary_lower.split_whitespace().map(|s| s.to_string()).collect()),
+        (2, doc.tags.iter().map(|t| t.to_lowercase()).collect()),
+    ];
+
+    let mut matched_words = std::collections::HashSet::new();
+    let mut total_typo = 0i32;
+    let mut exact_count = 0i32;
+    let mut best_attribute = i32::MAX;
+    let mut content_positions: Vec<usize> = Vec::new();
+
+    for term in &terms {
+        // AUTO's per-term budget: 0 edits for 1-2 chars, 1 for 3-5, 2 beyond.
+        let max_edits = match term.chars().count() {
+            0..=2 => 0,
+            3..=5 => 1,
+            _ => 2,
+        };
+        let mut best_typo_for_term: Option<usize> = None;
+        for (rank, words) in &fields {
+            for (pos, word) in words.iter().enumerate() {
+                let dist = levenshtein(term, word);
+                if dist <= max_edits {
+                    matched_words.insert(term.clone());
+                    best_attribute = best_attribute.min(*rank);
+                    if dist == 0 {
+                        exact_count += 1;
+                    }
+                    best_typo_for_term = Some(best_typo_for_term.map_or(dist, |d| d.min(dist)));
+                    if *rank == 0 {
+                        content_positions.push(pos);
+                    }
+                }
+            }
+        }
+        if let Some(dist) = best_typo_for_term {
+            total_typo += dist as i32;
+        }
+    }
+
+    content_positions.sort_unstable();
+    let proximity: i32 = content_positions
+        .windows(2)
+        .map(|w| (w[1] as i32 - w[0] as i32 - 1).max(0))
+        .sum();
+
+    RankingCriteria {
+        words: matched_words.len() as i32,
+        typo: total_typo,
+        proximity,
+        attribute: if best_attribute == i32::MAX { 3 } else { best_attribute },
+        exactness: exact_count,
+        importance,
+        recency: recency_score(created_at, settings.recency_half_life_days),
+    }
+}
+
+fn compute_combined_score(importance: f32, created_at: DateTime<Utc>, settings: &MemorySettings) -> f32 {
+    let recency = recency_score(created_at, settings.recency_half_life_days);
+    (importance * settings.importance_weight) + (recency * settings.recency_weight)
+}
+
+fn recency_score(created_at: DateTime<Utc>, half_life_days: f32) -> f32 {
     let age = Utc::now().signed_duration_since(created_at);
     let age_days = age.num_seconds().max(0) as f32 / (60.0 * 60.0 * 24.0);
-    (-age_days / 30.0).exp().clamp(0.0, 1.0)
+    0.5f32.powf(age_days / half_life_days).clamp(0.0, 1.0)
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "openmemory=info".into()),
-        )
-        .with_writer(std::io::stderr)
-        .init();
+// HTTP/SSE transport (opt-in alongside the default stdio loop): clients open
+// `GET /sse` and receive an `endpoint` event pointing them at `POST /messages`
+// for that session; JSON-RPC responses are delivered asynchronously back over
+// the SSE stream rather than as the POST response body. This mirrors the
+// stdio framing (one JSON-RPC message per logical exchange) while allowing
+// remote, concurrent clients instead of a single locally-spawned process.
+type SessionTx = mpsc::UnboundedSender<String>;
 
-    info!("openmemory MCP server starting (PostgreSQL + OpenSearch)");
+#[derive(Clone)]
+struct HttpState {
+    server: Arc<AsyncMutex<McpServer>>,
+    sessions: Arc<std::sync::Mutex<HashMap<String, SessionTx>>>,
+}
+
+#[derive(Deserialize)]
+struct SessionQuery {
+    #[serde(rename = "sessionId")]
+    session_id: String,
+}
+
+async fn sse_handler(
+    State(state): State<HttpState>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let session_id = Uuid::new_v4().to_string();
+    let (tx, rx) = mpsc::unbounded_channel::<String>();
+    state.sessions.lock().unwrap().insert(session_id.clone(), tx);
+
+    let endpoint = stream::once(async move {
+        Ok(Event::default()
+            .event("endpoint")
+            .data(format!("/messages?sessionId={session_id}")))
+    });
+    let messages =
+        UnboundedReceiverStream::new(rx).map(|msg| Ok(Event::default().event("message").data(msg)));
+
+    Sse::new(endpoint.chain(messages)).keep_alive(KeepAlive::default())
+}
+
+async fn messages_handler(
+    State(state): State<HttpState>,
+    Query(session): Query<SessionQuery>,
+    Json(req): Json<JsonRpcRequest>,
+) -> impl IntoResponse {
+    let tx = state.sessions.lock().unwrap().get(&session.session_id).cloned();
+    let Some(tx) = tx else {
+        return (StatusCode::NOT_FOUND, "unknown or expired sessionId").into_response();
+    };
+
+    let response = state.server.lock().await.handle_request(req).await;
+    match serde_json::to_string(&response) {
+        Ok(json) => {
+            let _ = tx.send(json);
+            StatusCode::ACCEPTED.into_response()
+        }
+        Err(e) => {
+            error!("failed to serialize response: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn metrics_handler(State(state): State<HttpState>) -> impl IntoResponse {
+    let server = state.server.lock().await;
+    let indexed = server.indexed_document_count().await;
+    let body = server.metrics.render(indexed);
+    drop(server);
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
+async fn health_handler(State(state): State<HttpState>) -> impl IntoResponse {
+    let (postgres_ok, opensearch_ok) = state.server.lock().await.is_healthy().await;
+    let status = if postgres_ok && opensearch_ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (
+        status,
+        Json(json!({
+            "status": if postgres_ok && opensearch_ok { "ok" } else { "degraded" },
+            "postgres": postgres_ok,
+            "opensearch": opensearch_ok,
+        })),
+    )
+}
+
+async fn run_http_transport(server: McpServer) -> Result<()> {
+    let port = std::env::var("OPENMEMORY_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(8765);
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+    let state = HttpState {
+        server: Arc::new(AsyncMutex::new(server)),
+        sessions: Arc::new(std::sync::Mutex::new(HashMap::new())),
+    };
+
+    let app = Router::new()
+        .route("/sse", get(sse_handler))
+        .route("/messages", post(messages_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/health", get(health_handler))
+        .with_state(state);
+
+    info!(%addr, "openmemory MCP server listening (HTTP/SSE transport)");
 
-    let mut server = McpServer::new().await?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind to {addr}"))?;
+
+    axum::serve(listener, app)
+        .await
+        .context("HTTP transport server error")?;
+
+    Ok(())
+}
+
+async fn run_stdio_transport(mut server: McpServer) -> Result<()> {
     let stdin = tokio::io::stdin();
     let mut stdout = tokio::io::stdout();
     let mut reader = BufReader::new(stdin);
@@ -520,8 +1941,9 @@ async fn main() -> Result<()> {
                     id: None,
                     result: None,
                     error: Some(JsonRpcError {
-                        code: -32700,
+                        code: ErrorKind::ParseError.rpc_code(),
                         message: format!("Parse error: {}", e),
+                        data: Some(ErrorKind::ParseError.data()),
                     }),
                 };
                 let response_json = serde_json::to_string(&error_response)?;
@@ -534,3 +1956,93 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "openmemory=info".into()),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    info!("openmemory MCP server starting (PostgreSQL + OpenSearch)");
+
+    let server = McpServer::new().await?;
+
+    let use_http = std::env::args().any(|a| a == "--http")
+        || std::env::var("OPENMEMORY_TRANSPORT").map(|v| v == "http").unwrap_or(false);
+
+    if use_http {
+        run_http_transport(server).await
+    } else {
+        run_stdio_transport(server).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(pairs: &[(&str, i64)]) -> VersionVector {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn vector_dominates_strictly_ahead_on_one_writer() {
+        let a = version(&[("w1", 2)]);
+        let b = version(&[("w1", 1)]);
+        assert!(vector_dominates(&a, &b));
+        assert!(!vector_dominates(&b, &a));
+    }
+
+    #[test]
+    fn vector_dominates_equal_is_not_dominance() {
+        let a = version(&[("w1", 1), ("w2", 1)]);
+        let b = version(&[("w1", 1), ("w2", 1)]);
+        assert!(!vector_dominates(&a, &b));
+        assert!(!vector_dominates(&b, &a));
+    }
+
+    #[test]
+    fn vector_dominates_concurrent_siblings_neither_dominates() {
+        // w1 raced ahead on "w1" but fell behind on "w2" (or never saw it) - a
+        // genuine concurrent sibling, not an update.
+        let a = version(&[("w1", 2), ("w2", 0)]);
+        let b = version(&[("w1", 1), ("w2", 1)]);
+        assert!(!vector_dominates(&a, &b));
+        assert!(!vector_dominates(&b, &a));
+    }
+
+    #[test]
+    fn vector_dominates_handles_writer_absent_from_one_side() {
+        let a = version(&[("w1", 1), ("w2", 1)]);
+        let b = version(&[("w1", 1)]);
+        assert!(vector_dominates(&a, &b));
+        assert!(!vector_dominates(&b, &a));
+    }
+
+    #[test]
+    fn vector_max_takes_the_higher_counter_per_writer() {
+        let a = version(&[("w1", 2), ("w2", 0)]);
+        let b = version(&[("w1", 1), ("w2", 3)]);
+        let merged = vector_max(&a, &b);
+        assert_eq!(merged, version(&[("w1", 2), ("w2", 3)]));
+    }
+
+    #[test]
+    fn vector_max_is_commutative() {
+        let a = version(&[("w1", 5)]);
+        let b = version(&[("w2", 7)]);
+        assert_eq!(vector_max(&a, &b), vector_max(&b, &a));
+    }
+
+    #[test]
+    fn causality_token_round_trips() {
+        let original = version(&[("w1", 3), ("w2", 9)]);
+        let token = encode_causality_token(&original);
+        let decoded = decode_causality_token(&token).expect("token should decode");
+        assert_eq!(decoded, original);
+    }
+}